@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::Path;
+
+use crate::scanner::{PARSER_VERSION, ParsedFile};
+
+/// What every scanned file parsed into last time, so unchanged files are not parsed again.
+/// Kept in the output folder as `.cache.json`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ScanCache {
+    /// Version of the generator and of the parser that wrote the cache, a different parser may
+    /// parse differently.
+    version: String,
+    files: BTreeMap<String, CachedFile>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CachedFile {
+    hash: u64,
+    parsed: ParsedFile,
+}
+
+impl Default for ScanCache {
+    fn default() -> Self {
+        Self {
+            version: version(),
+            files: BTreeMap::new(),
+        }
+    }
+}
+
+impl ScanCache {
+    /// Reads the cache of the previous build, starting over when it is missing or outdated.
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<Self>(&content).ok())
+            .filter(|cache| cache.version == version())
+            .unwrap_or_default()
+    }
+    
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+    
+    /// The parsed contents of `file`, if it has not changed since it was cached.
+    pub fn get(&self, file: &str, hash: u64) -> Option<&ParsedFile> {
+        self.files.get(file)
+            .filter(|cached| cached.hash == hash)
+            .map(|cached| &cached.parsed)
+    }
+    
+    pub fn insert(&mut self, file: String, hash: u64, parsed: ParsedFile) {
+        self.files.insert(file, CachedFile { hash, parsed });
+    }
+}
+
+fn version() -> String {
+    format!("{}+parser.{}", env!("CARGO_PKG_VERSION"), PARSER_VERSION)
+}
+
+pub fn hash_content(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
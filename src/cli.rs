@@ -0,0 +1,196 @@
+use clap::{Parser, ValueEnum};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+use crate::log::Level;
+
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Args {
+    /// Folder of .lua files to scan, also writes the result to --input
+    pub path: Option<PathBuf>,
+
+    /// Folder of .lua files to scan without writing docs.json, repeat to merge several
+    #[arg(long, conflicts_with = "path")]
+    pub src: Vec<PathBuf>,
+
+    /// Glob of files or folders to leave out of the scan, e.g. "**/vendor/**", repeat for several
+    #[arg(long, value_name = "GLOB")]
+    pub exclude: Vec<String>,
+
+    /// docs.json to read when no source folder is given
+    #[arg(long, default_value = "docs.json")]
+    pub input: PathBuf,
+
+    /// Folder the website is generated into
+    #[arg(long, default_value = "dist")]
+    pub output: PathBuf,
+
+    /// Folder containing the website template files
+    #[arg(long, default_value = "template")]
+    pub template_dir: PathBuf,
+
+    /// Output format of the generated documentation
+    #[arg(long, value_enum, default_value_t = Format::Html)]
+    pub format: Format,
+
+    /// Rebuild whenever a source or template file changes
+    #[arg(long)]
+    pub watch: bool,
+
+    /// Check --input for problems and exit without generating anything
+    #[arg(long)]
+    pub validate: bool,
+
+    /// Compare with an older docs.json and list the added, removed and changed functions instead of generating
+    #[arg(long, value_name = "OLD_DOCS_JSON")]
+    pub diff: Option<PathBuf>,
+
+    /// Parse lua from stdin and print its functions as docs.json, FILE is where the buffer is saved
+    #[arg(long, value_name = "FILE", num_args = 0..=1, default_missing_value = "stdin.lua")]
+    pub stdin: Option<PathBuf>,
+
+    /// Serve the output folder on localhost after building, on port 8000 unless one is given
+    #[arg(long, value_name = "PORT", num_args = 0..=1, default_missing_value = "8000")]
+    pub serve: Option<u16>,
+
+    /// Generate functions tagged --@private or --@internal instead of leaving them out
+    #[arg(long)]
+    pub include_private: bool,
+
+    /// Use everything before the last dot as the category, `Net.Http.Get` is listed as Get in Net.Http instead of Http.Get in Net
+    #[arg(long)]
+    pub nested_categories: bool,
+
+    /// Use the folder of each file as the category of its functions, lua/player/ is Player, a --@class still wins
+    #[arg(long)]
+    pub category_from_path: bool,
+
+    /// Only log the results, warnings and errors, leaving out every [ INFO ] line
+    #[arg(long, short)]
+    pub quiet: bool,
+
+    /// Also log every scanned file and what was found in it
+    #[arg(long, short, conflicts_with = "quiet")]
+    pub verbose: bool,
+
+    /// Write every warning with its file and line to diagnostics.json in the output folder
+    #[arg(long)]
+    pub diagnostics: bool,
+
+    /// Fail instead of warning when the documentation has problems
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Fail when an included function has no description, listing each one with its file and line
+    #[arg(long)]
+    pub require_descriptions: bool,
+
+    /// Base URL that source files are linked under, e.g. https://github.com/org/repo/blob/main/lua
+    #[arg(long)]
+    pub repo_url: Option<String>,
+
+    /// Link author names to the email address given with them in `--@author Name <email>`
+    #[arg(long)]
+    pub link_authors: bool,
+
+    /// Link every function to a sandbox to try it in, {name} and {category} in the URL are filled in
+    #[arg(long, value_name = "URL")]
+    pub sandbox_url: Option<String>,
+
+    /// Syntax highlight signatures and examples at build time
+    #[arg(long)]
+    pub highlight: bool,
+
+    /// Shrink the generated pages, stylesheets and scripts by dropping whitespace and comments
+    #[arg(long)]
+    pub minify: bool,
+
+    /// Render every category into one self-contained index.html
+    #[arg(long)]
+    pub single_page: bool,
+
+    /// Also write the single-page documentation to this PDF file, with a table of contents
+    #[arg(long, value_name = "FILE")]
+    pub pdf: Option<PathBuf>,
+
+    /// Program turning the page into a PDF, takes the same arguments as wkhtmltopdf
+    #[arg(long, value_name = "PATH", default_value = "wkhtmltopdf")]
+    pub pdf_converter: PathBuf,
+
+    /// Also write the API as plain text to api.txt in the output folder, to paste into an LLM prompt
+    #[arg(long)]
+    pub text: bool,
+
+    /// Make index.html redirect to the first category instead of listing all of them
+    #[arg(long)]
+    pub redirect_index: bool,
+
+    /// Public URL the site is hosted at, writes a sitemap.xml and canonical links when set and prefixes links between pages with its path
+    #[arg(long)]
+    pub base_url: Option<String>,
+
+    /// Project name shown in the page titles and the sidebar
+    #[arg(long, default_value = "Documentation")]
+    pub title: String,
+
+    /// Image shown next to the title in the sidebar, copied into the output folder
+    #[arg(long)]
+    pub logo: Option<PathBuf>,
+
+    /// Icon shown in the browser tab, copied into the output folder, a default one is used without it
+    #[arg(long, value_name = "FILE")]
+    pub favicon: Option<PathBuf>,
+
+    /// Also write a manifest.webmanifest so the site can be installed as an app
+    #[arg(long)]
+    pub manifest: bool,
+
+    /// Color theme shown until the reader picks one, follows their system when unset
+    #[arg(long, value_enum)]
+    pub theme: Option<Theme>,
+
+    /// Language to document in, such as `fr`, shows `--@desc:fr` descriptions where a function has one
+    #[arg(long)]
+    pub locale: Option<String>,
+
+    /// Config file providing defaults for these options
+    #[arg(long, default_value = "xenorlua.toml")]
+    pub config: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Html,
+    Markdown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    Dark,
+    Light,
+}
+
+impl Args {
+    /// How much is logged, from `--quiet` and `--verbose`. With `--stdin` the output is only the JSON.
+    pub fn log_level(&self) -> Level {
+        if self.quiet || self.stdin.is_some() {
+            Level::Quiet
+        } else if self.verbose {
+            Level::Verbose
+        } else {
+            Level::Normal
+        }
+    }
+}
+
+impl Theme {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cli::{Args, Format, Theme};
+use crate::log;
+
+/// Options read from `xenorlua.toml`, every key mirrors the command line flag of the same name.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    pub src: Option<Sources>,
+    pub exclude: Option<Vec<String>>,
+    pub input: Option<PathBuf>,
+    pub output: Option<PathBuf>,
+    pub template_dir: Option<PathBuf>,
+    pub format: Option<Format>,
+    pub strict: Option<bool>,
+    pub require_descriptions: Option<bool>,
+    pub diagnostics: Option<bool>,
+    pub quiet: Option<bool>,
+    pub verbose: Option<bool>,
+    pub include_private: Option<bool>,
+    pub nested_categories: Option<bool>,
+    pub category_from_path: Option<bool>,
+    pub repo_url: Option<String>,
+    pub link_authors: Option<bool>,
+    pub sandbox_url: Option<String>,
+    pub highlight: Option<bool>,
+    pub minify: Option<bool>,
+    pub single_page: Option<bool>,
+    pub pdf: Option<PathBuf>,
+    pub pdf_converter: Option<PathBuf>,
+    pub text: Option<bool>,
+    pub redirect_index: Option<bool>,
+    pub base_url: Option<String>,
+    pub title: Option<String>,
+    pub logo: Option<PathBuf>,
+    pub favicon: Option<PathBuf>,
+    pub manifest: Option<bool>,
+    pub theme: Option<Theme>,
+    pub locale: Option<String>,
+}
+
+/// `src` takes one folder or a list of them.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Sources {
+    One(PathBuf),
+    Many(Vec<PathBuf>),
+}
+
+impl Sources {
+    fn into_vec(self) -> Vec<PathBuf> {
+        match self {
+            Sources::One(path) => vec![path],
+            Sources::Many(paths) => paths,
+        }
+    }
+}
+
+/// Parses the command line on top of the config file: defaults < file < flags.
+pub fn load_args() -> Result<Args, Box<dyn std::error::Error>> {
+    let matches = Args::command().get_matches();
+    let mut args = Args::from_arg_matches(&matches).unwrap_or_else(|error| error.exit());
+    
+    // the default file is optional, one passed with --config is not
+    let use_config = args.config.exists() || from_cli(&matches, "config");
+    if use_config {
+        load_config(&args.config)?.merge(&mut args, &matches);
+    }
+    
+    // the config can set --quiet too, so it is only mentioned once the level is known
+    log::set_level(args.log_level());
+    if use_config {
+        log::info!("Using config {}", args.config.display());
+    }
+    
+    Ok(args)
+}
+
+/// Reads a config file, paths in it are relative to the file itself.
+pub fn load_config(path: &Path) -> Result<Config, Box<dyn std::error::Error>> {
+    let content = fs::read_to_string(path)
+        .map_err(|error| format!("could not read {}: {}", path.display(), error))?;
+    let mut config: Config = toml::from_str(&content)
+        .map_err(|error| format!("invalid config {}: {}", path.display(), error))?;
+    
+    let base = path.parent().unwrap_or(Path::new(""));
+    let sources = config.src.iter_mut().flat_map(|src| match src {
+        Sources::One(path) => std::slice::from_mut(path),
+        Sources::Many(paths) => paths.as_mut_slice(),
+    });
+    let paths = [&mut config.input, &mut config.output, &mut config.template_dir, &mut config.logo, &mut config.favicon, &mut config.pdf];
+    for path in paths.into_iter().flatten().chain(sources) {
+        *path = base.join(&*path);
+    }
+    
+    Ok(config)
+}
+
+impl Config {
+    /// Fills in every option that was not passed on the command line.
+    fn merge(self, args: &mut Args, matches: &ArgMatches) {
+        // a positional folder already picks the source
+        if args.path.is_none() {
+            set(matches, "src", &mut args.src, self.src.map(Sources::into_vec));
+        }
+        set(matches, "exclude", &mut args.exclude, self.exclude);
+        set(matches, "input", &mut args.input, self.input);
+        set(matches, "output", &mut args.output, self.output);
+        set(matches, "template_dir", &mut args.template_dir, self.template_dir);
+        set(matches, "format", &mut args.format, self.format);
+        set(matches, "strict", &mut args.strict, self.strict);
+        set(matches, "require_descriptions", &mut args.require_descriptions, self.require_descriptions);
+        set(matches, "diagnostics", &mut args.diagnostics, self.diagnostics);
+        // both pick the log level, so either flag on the command line overrides both keys
+        if !from_cli(matches, "quiet") && !from_cli(matches, "verbose") {
+            set(matches, "quiet", &mut args.quiet, self.quiet);
+            set(matches, "verbose", &mut args.verbose, self.verbose);
+        }
+        set(matches, "include_private", &mut args.include_private, self.include_private);
+        set(matches, "nested_categories", &mut args.nested_categories, self.nested_categories);
+        set(matches, "category_from_path", &mut args.category_from_path, self.category_from_path);
+        set(matches, "repo_url", &mut args.repo_url, self.repo_url.map(Some));
+        set(matches, "link_authors", &mut args.link_authors, self.link_authors);
+        set(matches, "sandbox_url", &mut args.sandbox_url, self.sandbox_url.map(Some));
+        set(matches, "highlight", &mut args.highlight, self.highlight);
+        set(matches, "minify", &mut args.minify, self.minify);
+        set(matches, "single_page", &mut args.single_page, self.single_page);
+        set(matches, "pdf", &mut args.pdf, self.pdf.map(Some));
+        set(matches, "pdf_converter", &mut args.pdf_converter, self.pdf_converter);
+        set(matches, "text", &mut args.text, self.text);
+        set(matches, "redirect_index", &mut args.redirect_index, self.redirect_index);
+        set(matches, "base_url", &mut args.base_url, self.base_url.map(Some));
+        set(matches, "title", &mut args.title, self.title);
+        set(matches, "logo", &mut args.logo, self.logo.map(Some));
+        set(matches, "favicon", &mut args.favicon, self.favicon.map(Some));
+        set(matches, "manifest", &mut args.manifest, self.manifest);
+        set(matches, "theme", &mut args.theme, self.theme.map(Some));
+        set(matches, "locale", &mut args.locale, self.locale.map(Some));
+    }
+}
+
+fn from_cli(matches: &ArgMatches, id: &str) -> bool {
+    matches.value_source(id) == Some(ValueSource::CommandLine)
+}
+
+fn set<T>(matches: &ArgMatches, id: &str, target: &mut T, value: Option<T>) {
+    if let Some(value) = value
+        && !from_cli(matches, id)
+    {
+        *target = value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log::Level;
+    
+    fn merged(config: Config, flags: &[&str]) -> Args {
+        let matches = Args::command().get_matches_from(std::iter::once("XenorLuaGenerator").chain(flags.iter().copied()));
+        let mut args = Args::from_arg_matches(&matches).unwrap();
+        config.merge(&mut args, &matches);
+        args
+    }
+    
+    #[test]
+    fn log_level_flags_override_both_config_keys() {
+        let quiet = || Config { quiet: Some(true), ..Config::default() };
+        assert_eq!(merged(quiet(), &["--verbose"]).log_level(), Level::Verbose);
+        assert_eq!(merged(quiet(), &[]).log_level(), Level::Quiet);
+        
+        let verbose = Config { verbose: Some(true), ..Config::default() };
+        assert_eq!(merged(verbose, &["--quiet"]).log_level(), Level::Quiet);
+    }
+}
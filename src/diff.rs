@@ -0,0 +1,162 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use crate::cli::Format;
+use crate::log;
+use crate::render_signature;
+use crate::scanner::{Documentation, Function};
+
+/// How one category changed, listed under its current name.
+#[derive(Default)]
+struct CategoryDiff<'a> {
+    /// Name the category had in the old docs, when it was renamed.
+    renamed_from: Option<&'a str>,
+    added: Vec<String>,
+    removed: Vec<String>,
+    /// Signatures before and after, parameters or returns differ.
+    changed: Vec<(String, String)>,
+}
+
+impl CategoryDiff<'_> {
+    fn is_empty(&self) -> bool {
+        self.renamed_from.is_none() && self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Prints what changed in the API between the docs.json at `old_path` and `new`, for `--diff`.
+pub fn print_diff(old_path: &Path, old: &Documentation, new: &Documentation, format: Format) {
+    let diff = diff_docs(old, new);
+    if diff.is_empty() {
+        log::ok_stderr!("No API changes since {}", old_path.display());
+        return;
+    }
+    
+    let (added, removed, changed) = diff.values().fold((0, 0, 0), |(added, removed, changed), category| {
+        (added + category.added.len(), removed + category.removed.len(), changed + category.changed.len())
+    });
+    
+    if format == Format::Markdown {
+        print!("{}", render_markdown(&diff));
+    } else {
+        log::info!("Changes since {}", old_path.display());
+        print!("{}", render_text(&diff));
+    }
+    log::ok_stderr!("{} added, {} removed, {} changed", added, removed, changed);
+}
+
+fn diff_docs<'a>(old: &'a Documentation, new: &'a Documentation) -> BTreeMap<&'a str, CategoryDiff<'a>> {
+    let renames = find_renames(old, new);
+    let mut diff: BTreeMap<&str, CategoryDiff> = BTreeMap::new();
+    
+    for (category, functions) in new {
+        let renamed_from = renames.get(category.as_str()).copied();
+        let previous = old.get(renamed_from.unwrap_or(category)).map_or(&[][..], Vec::as_slice);
+        let entry = diff.entry(category).or_default();
+        entry.renamed_from = renamed_from;
+        compare_functions(category, previous, functions, entry);
+    }
+    
+    let renamed: BTreeSet<&str> = renames.values().copied().collect();
+    for (category, functions) in old {
+        if !new.contains_key(category) && !renamed.contains(category.as_str()) {
+            compare_functions(category, functions, &[], diff.entry(category).or_default());
+        }
+    }
+    
+    diff.retain(|_, category| !category.is_empty());
+    diff
+}
+
+/// Pairs categories that only exist in `new` with one that only exists in `old`, when at least
+/// half of the old one's functions are found in it under the same names.
+fn find_renames<'a>(old: &'a Documentation, new: &'a Documentation) -> BTreeMap<&'a str, &'a str> {
+    let mut renames = BTreeMap::new();
+    
+    for (old_category, old_functions) in old.iter().filter(|(category, _)| !new.contains_key(*category)) {
+        let names: BTreeSet<&str> = old_functions.iter().map(|func| func.name.as_str()).collect();
+        let best = new.iter()
+            .filter(|(category, _)| !old.contains_key(*category) && !renames.contains_key(category.as_str()))
+            .map(|(category, functions)| {
+                let shared = functions.iter().filter(|func| names.contains(func.name.as_str())).count();
+                (category, shared)
+            })
+            .max_by_key(|(_, shared)| *shared);
+        
+        if let Some((new_category, shared)) = best
+            && shared > 0
+            && shared * 2 >= names.len()
+        {
+            renames.insert(new_category.as_str(), old_category.as_str());
+        }
+    }
+    
+    renames
+}
+
+/// Functions are matched by name, signatures are rendered under the current category name so
+/// a renamed category alone does not count as a change.
+fn compare_functions(category: &str, old: &[Function], new: &[Function], diff: &mut CategoryDiff) {
+    for func in new {
+        match old.iter().find(|previous| previous.name == func.name) {
+            Some(previous) => {
+                let before = render_signature(category, previous);
+                let after = render_signature(category, func);
+                if before != after {
+                    diff.changed.push((before, after));
+                }
+            }
+            None => diff.added.push(render_signature(category, func)),
+        }
+    }
+    
+    for func in old {
+        if !new.iter().any(|current| current.name == func.name) {
+            diff.removed.push(render_signature(category, func));
+        }
+    }
+}
+
+fn render_text(diff: &BTreeMap<&str, CategoryDiff>) -> String {
+    let mut text = String::new();
+    for (category, changes) in diff {
+        match changes.renamed_from {
+            Some(old_name) => text.push_str(&format!("\n{} (renamed from {})\n", category, old_name)),
+            None => text.push_str(&format!("\n{}\n", category)),
+        }
+        for signature in &changes.added {
+            text.push_str(&format!("  + {}\n", signature));
+        }
+        for signature in &changes.removed {
+            text.push_str(&format!("  - {}\n", signature));
+        }
+        for (before, after) in &changes.changed {
+            text.push_str(&format!("  ~ {}\n    → {}\n", before, after));
+        }
+    }
+    text
+}
+
+/// The same changes as a changelog section, one heading per category.
+fn render_markdown(diff: &BTreeMap<&str, CategoryDiff>) -> String {
+    let mut markdown = String::new();
+    for (category, changes) in diff {
+        markdown.push_str(&format!("### {}\n\n", category));
+        if let Some(old_name) = changes.renamed_from {
+            markdown.push_str(&format!("*Renamed from {}*\n\n", old_name));
+        }
+        let list_start = markdown.len();
+        for signature in &changes.added {
+            markdown.push_str(&format!("- **Added** `{}`\n", signature));
+        }
+        for signature in &changes.removed {
+            markdown.push_str(&format!("- **Removed** `{}`\n", signature));
+        }
+        for (before, after) in &changes.changed {
+            markdown.push_str(&format!("- **Changed** `{}` → `{}`\n", before, after));
+        }
+        if markdown.len() > list_start {
+            markdown.push('\n');
+        }
+    }
+    markdown
+}
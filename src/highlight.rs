@@ -0,0 +1,38 @@
+use syntect::highlighting::ThemeSet;
+use syntect::html::{ClassStyle, ClassedHTMLGenerator, css_for_theme_with_class_style};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+/// Prefix of the generated classes so they can't clash with the template's own.
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "hl-" };
+const THEME: &str = "base16-ocean.dark";
+
+/// Turns Lua code into static HTML spans at build time, styled by the CSS from `css()`.
+pub struct Highlighter {
+    syntax_set: SyntaxSet,
+}
+
+impl Highlighter {
+    pub fn new() -> Self {
+        Self { syntax_set: SyntaxSet::load_defaults_newlines() }
+    }
+    
+    /// Highlighted, already escaped HTML for a piece of Lua code.
+    pub fn highlight_lua(&self, code: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let syntax = self.syntax_set
+            .find_syntax_by_name("Lua")
+            .unwrap_or_else(|| self.syntax_set.find_syntax_plain_text());
+        
+        let mut generator = ClassedHTMLGenerator::new_with_class_style(syntax, &self.syntax_set, CLASS_STYLE);
+        for line in LinesWithEndings::from(code) {
+            generator.parse_html_for_line_which_includes_newline(line)?;
+        }
+        Ok(generator.finalize())
+    }
+    
+    /// Stylesheet for the highlighted spans.
+    pub fn css() -> Result<String, Box<dyn std::error::Error>> {
+        let themes = ThemeSet::load_defaults();
+        Ok(css_for_theme_with_class_style(&themes.themes[THEME], CLASS_STYLE)?)
+    }
+}
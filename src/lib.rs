@@ -0,0 +1,1774 @@
+//! Scans lua files for doc comments and generates a documentation website from them.
+//!
+//! The command line is a thin layer over this library: [`scan_directory`] and
+//! [`parse_lua_content`] read the documentation, [`generate`] writes the website for it.
+
+use rayon::prelude::*;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+mod cache;
+pub mod cli;
+pub mod config;
+mod diff;
+mod highlight;
+mod log;
+mod markdown;
+mod minify;
+mod navigation;
+mod pdf;
+mod scanner;
+mod serve;
+mod text;
+mod validate;
+mod watch;
+use cli::{Args, Format, Theme};
+use cache::ScanCache;
+use highlight::Highlighter;
+use navigation::{Navigation, anchor_id, anchor_ids, category_enums, enum_anchor, group_functions, page_href, page_names, render_toc, single_page_anchor, slugify};
+use scanner::BackReferences;
+pub use scanner::{Categories, Documentation, Enum, EnumVariant, Enums, Field, Function, Param, Return, Scanned};
+pub use validate::{Diagnostic, Severity};
+
+/// Scans every `.lua` file below `dir`, the way `--path` does without writing a docs.json.
+pub fn scan_directory(dir: &Path) -> Result<Scanned, Box<dyn std::error::Error>> {
+    let exclude = scanner::exclude_set(&[])?;
+    scanner::scan_directories(&[dir.to_path_buf()], &exclude, false, false, &mut ScanCache::default())
+}
+
+/// Documents the content of a single lua file, `path` is where it is saved, for the name of its
+/// module and as the source of every function.
+pub fn parse_lua_content(content: &str, path: &Path) -> Scanned {
+    scanner::scan_content(content, path, false, false)
+}
+
+/// Writes the website of what was scanned into `options.output`, like a build does. Functions
+/// tagged private are left out unless `options.include_private` is set. For a docs.json, which
+/// only holds the functions, use `Scanned { docs, ..Scanned::default() }`.
+pub fn generate(scanned: &Scanned, options: &Options) -> Result<(), Box<dyn std::error::Error>> {
+    let mut docs = scanned.docs.clone();
+    if !options.include_private {
+        remove_private(&mut docs, &scanned.enums);
+    }
+    remove_empty_categories(&mut docs, &scanned.enums);
+    if let Some(locale) = &options.locale {
+        localize(&mut docs, locale);
+    }
+    fs::create_dir_all(&options.output)?;
+    generate_html(&docs, &scanned.categories, &scanned.enums, options)
+}
+
+/// Runs the command line: a build, or whichever of `--validate`, `--stdin` and `--diff` is given.
+pub fn run(args: Args) -> Result<(), Box<dyn std::error::Error>> {
+    if args.validate {
+        return validate::validate_docs_json(&args.input);
+    }
+    if let Some(path) = &args.stdin {
+        let content = std::io::read_to_string(std::io::stdin())?;
+        let scanned = scanner::scan_content(&content, path, args.nested_categories, args.category_from_path);
+        println!("{}", serde_json::to_string_pretty(&scanned.docs)?);
+        return Ok(());
+    }
+    if let Some(old_path) = &args.diff {
+        let old: Documentation = serde_json::from_str(&read_file(old_path)?)
+            .map_err(|error| format!("{} is not a valid docs.json: {}", old_path.display(), error))?;
+        diff::print_diff(old_path, &old, &current_docs(&args)?, args.format);
+        return Ok(());
+    }
+    
+    build(&args)?;
+    
+    let server = args.serve
+        .map(|port| serve::spawn(args.output.clone(), port, args.base_url.as_deref().map_or_else(String::new, base_path)))
+        .transpose()?;
+    
+    if args.watch {
+        watch::watch(&args, || build(&args))?;
+    } else if let Some(server) = server {
+        server.join().map_err(|_| "The preview server stopped unexpectedly")?;
+    }
+    
+    Ok(())
+}
+
+/// Runs the whole scan-and-generate pipeline once.
+fn build(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let scanned = !args.src.is_empty() || args.path.is_some();
+    
+    let cache_path = args.output.join(".cache.json");
+    let mut cache = ScanCache::load(&cache_path);
+    let exclude = scanner::exclude_set(&args.exclude)?;
+    
+    // category descriptions and enums only come from scanning, docs.json holds just the functions
+    let scanned_docs = if !args.src.is_empty() {
+        for path in &args.src {
+            log::info!("Scanning directory: {}", path.display());
+        }
+        scanner::scan_directories(&args.src, &exclude, args.nested_categories, args.category_from_path, &mut cache)?
+    } else if let Some(path) = &args.path {
+        log::info!("Scanning directory: {}", path.display());
+        let scanned_docs = scanner::scan_directories(std::slice::from_ref(path), &exclude, args.nested_categories, args.category_from_path, &mut cache)?;
+        
+        write_docs_json(&scanned_docs.docs, &args.input)?;
+        log::ok!("Generated {} from source files", args.input.display());
+        
+        scanned_docs
+    } else {
+        log::info!("No directory specified, reading existing {}", args.input.display());
+        let json_content = read_file(&args.input)?;
+        Scanned {
+            docs: serde_json::from_str(&json_content)?,
+            categories: Categories::new(),
+            enums: Enums::new(),
+            diagnostics: Vec::new(),
+        }
+    };
+    let Scanned { mut docs, categories, enums, mut diagnostics } = scanned_docs;
+    
+    let duplicates = validate::report_duplicates(&docs, &mut diagnostics);
+    let collisions = validate::report_alias_collisions(&docs, &mut diagnostics);
+    let mismatches = validate::report_param_mismatches(&docs, &mut diagnostics);
+    let unresolved = validate::report_unresolved_references(&docs, &mut diagnostics);
+    
+    if !args.include_private {
+        let hidden = remove_private(&mut docs, &enums);
+        if hidden > 0 {
+            log::info!("Leaving out {} private functions, pass --include-private to document them", hidden);
+        }
+    }
+    
+    // after leaving out private functions, only what ends up in the documentation needs a description
+    let undescribed = validate::report_undescribed(&docs, &mut diagnostics, args.require_descriptions);
+    
+    let failure = if args.strict && duplicates > 0 {
+        Some(format!("{} duplicate functions found", duplicates))
+    } else if args.strict && collisions > 0 {
+        Some(format!("{} aliases collide with documented functions", collisions))
+    } else if args.strict && mismatches > 0 {
+        Some(format!("{} parameters do not match their declaration", mismatches))
+    } else if args.require_descriptions && undescribed > 0 {
+        Some(format!("{} functions have no description", undescribed))
+    } else {
+        None
+    };
+    // the diagnostics of a failing build still replace the ones of the last build that passed
+    if let Some(failure) = failure {
+        if args.diagnostics {
+            write_diagnostics(&diagnostics, &args.output)?;
+        }
+        return Err(failure.into());
+    }
+    
+    remove_empty_categories(&mut docs, &enums);
+    if docs.is_empty() {
+        let source = if scanned { String::from("the scanned files") } else { args.input.display().to_string() };
+        log::warning!("No documentation found in {}, the site will only say so", source);
+    }
+    
+    let dist_path = args.output.as_path();
+    if dist_path.exists() {
+        fs::remove_dir_all(dist_path)?;
+    }
+    fs::create_dir_all(dist_path)?;
+    
+    if scanned {
+        write_docs_json(&docs, &dist_path.join("docs.json"))?;
+        cache.save(&cache_path)?;
+    }
+    
+    if args.diagnostics {
+        write_diagnostics(&diagnostics, dist_path)?;
+    }
+    
+    // docs.json keeps every language, only what is rendered is localized
+    if let Some(locale) = &args.locale {
+        localize(&mut docs, locale);
+    }
+    
+    let options = Options::from(args);
+    match args.format {
+        Format::Html => generate_html(&docs, &categories, &enums, &options)?,
+        Format::Markdown => {
+            markdown::generate_markdown(&docs, &categories, &enums, dist_path, args.repo_url.as_deref())?
+        }
+    }
+    
+    if let Some(pdf_path) = &args.pdf {
+        // the manual is always the single page, whichever layout the website uses
+        let site = new_site(&options, &docs, &categories, &enums, true)?;
+        pdf::generate_pdf(&render_single_page(&site)?, dist_path, pdf_path, &args.pdf_converter)?;
+        log::ok!("Generated {}", pdf_path.display());
+    }
+    
+    if args.text {
+        text::generate_text(&docs, &categories, &args.title, dist_path)?;
+    }
+    
+    let (undescribed, undocumented) = validate::count_incomplete(&docs);
+    log::ok!(
+        "Generated {} categories, {} functions, {} warnings ({} without a description, {} with undocumented parameters)",
+        docs.len(),
+        docs.values().map(Vec::len).sum::<usize>(),
+        duplicates + collisions + mismatches + unresolved,
+        undescribed,
+        undocumented
+    );
+    log::ok!("Documentation generated in {}", dist_path.display());
+    Ok(())
+}
+
+/// The documentation `--diff` compares against, read the same way a build would but without writing anything.
+fn current_docs(args: &Args) -> Result<Documentation, Box<dyn std::error::Error>> {
+    let sources = match &args.path {
+        Some(path) => std::slice::from_ref(path),
+        None => args.src.as_slice(),
+    };
+    let mut docs = if sources.is_empty() {
+        serde_json::from_str(&read_file(&args.input)?)?
+    } else {
+        let exclude = scanner::exclude_set(&args.exclude)?;
+        scanner::scan_directories(sources, &exclude, args.nested_categories, args.category_from_path, &mut ScanCache::default())?.docs
+    };
+    
+    if !args.include_private {
+        remove_private(&mut docs, &Enums::new());
+    }
+    Ok(docs)
+}
+
+/// What the website looks like, the options of the command line that shape it.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// Folder containing the template files.
+    pub template_dir: PathBuf,
+    /// Folder the website is generated into.
+    pub output: PathBuf,
+    /// Project name shown in the page titles and the sidebar.
+    pub title: String,
+    /// Image shown next to the title, copied into `output`.
+    pub logo: Option<PathBuf>,
+    /// Icon shown in the browser tab, a built-in one is used without it.
+    pub favicon: Option<PathBuf>,
+    /// Whether a `manifest.webmanifest` is written so the site can be installed as an app.
+    pub manifest: bool,
+    /// Theme shown until the reader picks one, their system's without it.
+    pub theme: Option<Theme>,
+    /// Language whose `--@desc:<locale>` descriptions are shown, such as `fr`.
+    pub locale: Option<String>,
+    /// Links every function to its source below this URL.
+    pub repo_url: Option<String>,
+    /// Whether functions tagged `--@private` or `--@internal` are generated too.
+    pub include_private: bool,
+    /// Whether author names link to their email address.
+    pub link_authors: bool,
+    /// URL of a sandbox to try a function in, with `{name}` and `{category}` filled in.
+    pub sandbox_url: Option<String>,
+    /// Whether signatures and examples are highlighted while building.
+    pub highlight: bool,
+    /// Whether pages and assets are written minified.
+    pub minify: bool,
+    /// Everything on one `index.html` instead of a page per category.
+    pub single_page: bool,
+    /// Whether `index.html` forwards to the first category instead of listing them.
+    pub redirect_index: bool,
+    /// Public URL the site is hosted at, for the sitemap and canonical links.
+    pub base_url: Option<String>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            template_dir: PathBuf::from("template"),
+            output: PathBuf::from("dist"),
+            title: String::from("Documentation"),
+            logo: None,
+            favicon: None,
+            manifest: false,
+            theme: None,
+            locale: None,
+            repo_url: None,
+            include_private: false,
+            link_authors: false,
+            sandbox_url: None,
+            highlight: false,
+            minify: false,
+            single_page: false,
+            redirect_index: false,
+            base_url: None,
+        }
+    }
+}
+
+impl From<&Args> for Options {
+    fn from(args: &Args) -> Self {
+        Options {
+            template_dir: args.template_dir.clone(),
+            output: args.output.clone(),
+            title: args.title.clone(),
+            logo: args.logo.clone(),
+            favicon: args.favicon.clone(),
+            manifest: args.manifest,
+            theme: args.theme,
+            locale: args.locale.clone(),
+            repo_url: args.repo_url.clone(),
+            include_private: args.include_private,
+            link_authors: args.link_authors,
+            sandbox_url: args.sandbox_url.clone(),
+            highlight: args.highlight,
+            minify: args.minify,
+            single_page: args.single_page,
+            redirect_index: args.redirect_index,
+            base_url: args.base_url.clone(),
+        }
+    }
+}
+
+/// Writes the website, as one page or one page per category.
+fn generate_html(
+    docs: &Documentation,
+    categories: &Categories,
+    enums: &Enums,
+    options: &Options
+) -> Result<(), Box<dyn std::error::Error>> {
+    let site = new_site(options, docs, categories, enums, options.single_page)?;
+    
+    if options.single_page {
+        generate_single_page(&site)
+    } else {
+        generate_site(&site, options.redirect_index, options.base_url.as_deref())
+    }
+}
+
+/// Pages of the website besides the category pages, which no category page may be named like.
+const SITE_PAGES: &[&str] = &["index", "index-az", "authors"];
+
+/// The website of `options`, laid out as one page or one page per category.
+fn new_site<'a>(
+    options: &'a Options,
+    docs: &'a Documentation,
+    categories: &'a Categories,
+    enums: &'a Enums,
+    single_page: bool
+) -> Result<Site<'a>, Box<dyn std::error::Error>> {
+    let logo = match &options.logo {
+        Some(logo) => copy_logo(logo, &options.output)?,
+        None => String::new(),
+    };
+    let base = options.base_url.as_deref().map_or_else(String::new, base_path);
+    
+    let icon = copy_favicon(options.favicon.as_deref(), &options.output)?;
+    let mut favicon = format!(r#"
+    <link rel="icon" href="{}">"#, escape_html(&icon));
+    if options.manifest {
+        generate_manifest(&options.title, &icon, &options.output)?;
+        favicon.push_str(r#"
+    <link rel="manifest" href="manifest.webmanifest">"#);
+    }
+    
+    let pages = page_names(docs, SITE_PAGES);
+    Ok(Site {
+        docs,
+        categories,
+        enums,
+        navigation: if single_page {
+            Navigation::single_page(docs, enums)
+        } else {
+            Navigation::new(docs, enums, &pages, &base)
+        },
+        template_dir: &options.template_dir,
+        out_dir: &options.output,
+        repo_url: options.repo_url.as_deref(),
+        link_authors: options.link_authors,
+        sandbox_url: options.sandbox_url.as_deref(),
+        highlighter: options.highlight.then(Highlighter::new),
+        title: &options.title,
+        logo,
+        favicon,
+        theme: options.theme.map_or("", Theme::as_str),
+        lang: options.locale.as_deref().unwrap_or("en"),
+        single_page,
+        minify: options.minify,
+        home: if options.redirect_index {
+            // index.html only forwards to the first category, so link there directly
+            pages.values().next().map_or_else(|| format!("{}index.html", base), |page| {
+                format!("{}{}.html", base, page)
+            })
+        } else {
+            format!("{}index.html", base)
+        },
+        base_url: options.base_url.as_deref(),
+        base,
+        pages,
+        references: scanner::back_references(docs),
+    })
+}
+
+/// Drops the categories with neither functions nor enums, like `"Player": []` in a hand-written docs.json.
+fn remove_empty_categories(docs: &mut Documentation, enums: &Enums) {
+    docs.retain(|category, functions| {
+        let empty = functions.is_empty() && !enums.contains_key(category);
+        if empty {
+            log::warning!("Skipping category {}, it has no functions", category);
+        }
+        !empty
+    });
+}
+
+/// Swaps in the `--@desc:<locale>` description of every function that has one for `locale`.
+fn localize(docs: &mut Documentation, locale: &str) {
+    for func in docs.values_mut().flatten() {
+        if let Some(description) = func.descriptions.remove(locale) {
+            func.description = description;
+        }
+    }
+}
+
+/// Drops the functions tagged private, and the categories only they were in, returning how many were dropped.
+fn remove_private(docs: &mut Documentation, enums: &Enums) -> usize {
+    let mut removed = 0;
+    docs.retain(|category, functions| {
+        let count = functions.len();
+        functions.retain(|func| !func.private);
+        removed += count - functions.len();
+        count == functions.len() || !functions.is_empty() || enums.contains_key(category)
+    });
+    removed
+}
+
+/// Writes the multi-page website: one page per category plus the shared assets.
+fn generate_site(
+    site: &Site,
+    redirect_index: bool,
+    base_url: Option<&str>
+) -> Result<(), Box<dyn std::error::Error>> {
+    let docs = site.docs;
+    
+    generate_css(site.template_dir, site.out_dir, site.highlighter.is_some(), site.minify)?;
+    generate_search_script(site.template_dir, site.out_dir, site.minify)?;
+    
+    docs.par_iter()
+        .map(|(category, functions)| {
+            generate_category_page(site, category, functions)
+                .map_err(|error| error.to_string())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    // without any category there is nothing to redirect to, the landing page says so instead
+    match docs.keys().next() {
+        Some(first_category) if redirect_index => generate_index_redirect(site, first_category)?,
+        _ => generate_landing_page(site)?,
+    }
+    generate_alphabetical_index(site)?;
+    generate_authors_page(site)?;
+    
+    generate_search_index(site)?;
+    
+    if let Some(base_url) = base_url {
+        generate_sitemap(site, base_url)?;
+    }
+    Ok(())
+}
+
+/// Copies the `--logo` image next to the pages and returns the markup showing it.
+fn copy_logo(logo: &Path, out_dir: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let file_name = logo.file_name()
+        .ok_or_else(|| format!("invalid logo path {}", logo.display()))?;
+    fs::copy(logo, out_dir.join(file_name))
+        .map_err(|error| format!("could not copy {}: {}", logo.display(), error))?;
+    
+    Ok(format!(r#"<img class="site-logo" src="{}" alt="">"#, escape_html(&file_name.to_string_lossy())))
+}
+
+/// Icon used when no `--favicon` is given.
+const DEFAULT_FAVICON: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 64 64"><circle cx="30" cy="34" r="26" fill="#000080"/><circle cx="42" cy="22" r="8" fill="#ffffff"/><circle cx="56" cy="8" r="7" fill="#000080"/></svg>"##;
+
+/// Copies the `--favicon` next to the pages, or writes the default one, and returns its file name.
+fn copy_favicon(favicon: Option<&Path>, out_dir: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let Some(favicon) = favicon else {
+        fs::write(out_dir.join("favicon.svg"), DEFAULT_FAVICON)?;
+        return Ok(String::from("favicon.svg"));
+    };
+    
+    let file_name = favicon.file_name()
+        .ok_or_else(|| format!("invalid favicon path {}", favicon.display()))?;
+    fs::copy(favicon, out_dir.join(file_name))
+        .map_err(|error| format!("could not copy {}: {}", favicon.display(), error))?;
+    Ok(file_name.to_string_lossy().into_owned())
+}
+
+/// Writes manifest.webmanifest, which lets browsers install the site as an app.
+fn generate_manifest(title: &str, icon: &str, out_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let manifest = serde_json::json!({
+        "name": title,
+        "short_name": title,
+        "start_url": ".",
+        "display": "standalone",
+        "background_color": "#1c1c1e",
+        "theme_color": "#1c1c1e",
+        "icons": [{ "src": icon, "sizes": "any" }],
+    });
+    fs::write(out_dir.join("manifest.webmanifest"), serde_json::to_string_pretty(&manifest)?)?;
+    Ok(())
+}
+
+/// Reads a file, naming it in the error so a missing template is easy to spot.
+fn read_file(path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    fs::read_to_string(path)
+        .map_err(|error| format!("could not read {}: {}", path.display(), error).into())
+}
+
+/// Writes `diagnostics.json` into `out_dir`, creating it for a build that fails before generating.
+fn write_diagnostics(diagnostics: &[Diagnostic], out_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    fs::create_dir_all(out_dir)?;
+    fs::write(out_dir.join("diagnostics.json"), serde_json::to_string_pretty(diagnostics)?)?;
+    Ok(())
+}
+
+/// Writes the documentation in the same format the generator reads back in.
+fn write_docs_json(docs: &Documentation, out: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let json_output = serde_json::to_string_pretty(docs)?;
+    fs::write(out, json_output)?;
+    Ok(())
+}
+
+/// The template's style.css, plus the highlighting theme when `--highlight` is set.
+fn stylesheet(template_dir: &Path, highlight: bool) -> Result<String, Box<dyn std::error::Error>> {
+    let mut stylesheet = read_file(&template_dir.join("style.css"))?;
+    if highlight {
+        stylesheet.push_str("\n\n/* Syntax highlighting */\n");
+        stylesheet.push_str(&Highlighter::css()?);
+    }
+    Ok(stylesheet)
+}
+
+fn generate_css(template_dir: &Path, out_dir: &Path, highlight: bool, minify: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let template_content = stylesheet(template_dir, highlight)?;
+    write_asset(&out_dir.join("style.css"), &template_content, minify.then_some(minify::css))?;
+    
+    // the light theme and the print layout are optional so older templates keep working
+    for optional in ["style-light.css", "print.css"] {
+        let path = template_dir.join(optional);
+        if path.exists() {
+            write_asset(&out_dir.join(optional), &read_file(&path)?, minify.then_some(minify::css))?;
+        }
+    }
+    Ok(())
+}
+
+fn generate_search_script(template_dir: &Path, out_dir: &Path, minify: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let template_content = read_file(&template_dir.join("search.js"))?;
+    write_asset(&out_dir.join("search.js"), &template_content, minify.then_some(minify::js))
+}
+
+/// Writes a page or asset, through `minifier` when `--minify` is set.
+fn write_asset(path: &Path, content: &str, minifier: Option<fn(&str) -> String>) -> Result<(), Box<dyn std::error::Error>> {
+    match minifier {
+        Some(minifier) => fs::write(path, minifier(content))?,
+        None => fs::write(path, content)?,
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct SearchEntry<'a> {
+    name: &'a str,
+    category: &'a str,
+    description: &'a str,
+    href: String,
+    tokens: Vec<String>,
+}
+
+/// Lowercased words of a function's name, category and description for the fuzzy search.
+/// Identifiers are also split at case changes, so `GetPlayerName` yields `player` too.
+fn search_tokens(name: &str, category: &str, description: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = Vec::new();
+    let mut push = |token: String| {
+        if token.chars().count() > 1 && !tokens.contains(&token) {
+            tokens.push(token);
+        }
+    };
+    
+    for text in [name, category, description] {
+        for word in text.split(|c: char| !c.is_alphanumeric()) {
+            push(word.to_lowercase());
+            
+            let mut part = String::new();
+            for c in word.chars() {
+                if c.is_uppercase() && part.chars().last().is_some_and(char::is_lowercase) {
+                    push(part.to_lowercase());
+                    part.clear();
+                }
+                part.push(c);
+            }
+            push(part.to_lowercase());
+        }
+    }
+    
+    tokens
+}
+
+fn search_entries<'a>(
+    docs: &'a Documentation,
+    href: impl Fn(&str, &str) -> String + Copy
+) -> Vec<SearchEntry<'a>> {
+    docs.iter()
+        .flat_map(|(category, functions)| functions.iter().zip(anchor_ids(functions)).flat_map(move |(func, id)| {
+            // aliases are found under their own name but lead to the same block
+            let names = std::iter::once(func.name.as_str())
+                .chain(func.aliases.iter().map(|alias| Function::alias_name(alias)));
+            names.map(move |name| SearchEntry {
+                name,
+                category,
+                description: &func.description,
+                href: href(category, &id),
+                tokens: search_tokens(name, category, &func.description),
+            })
+        }))
+        .collect()
+}
+
+/// Writes every function of every category to search-index.json so search works across pages.
+fn generate_search_index(site: &Site) -> Result<(), Box<dyn std::error::Error>> {
+    let entries = search_entries(site.docs, |category, name| format!("{}{}", site.base, page_href(site.page(category), name)));
+    
+    fs::write(site.out_dir.join("search-index.json"), serde_json::to_string(&entries)?)?;
+    Ok(())
+}
+
+/// Path of `--base-url` that links between pages start with, `/api/` for `https://example.com/api`.
+/// They then resolve under the subpath however a page was reached, links within a page stay `#anchor`s.
+/// Empty for a site at the root of its domain, so its links stay relative and still work opened from disk.
+fn base_path(base_url: &str) -> String {
+    let path = match base_url.split_once("://") {
+        Some((_, host_and_path)) => host_and_path.find('/').map_or("", |start| &host_and_path[start..]),
+        None => base_url,
+    };
+    match path.trim_matches('/') {
+        "" => String::new(),
+        path => format!("/{}/", path),
+    }
+}
+
+/// Writes sitemap.xml listing every category page under `base_url`.
+fn generate_sitemap(site: &Site, base_url: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let base_url = base_url.trim_end_matches('/');
+    
+    let mut sitemap = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#);
+    for page in site.pages.values() {
+        sitemap.push_str(&format!(r#"
+    <url>
+        <loc>{}</loc>
+    </url>"#, escape_html(&format!("{}/{}.html", base_url, page))));
+    }
+    sitemap.push_str("\n</urlset>\n");
+    
+    fs::write(site.out_dir.join("sitemap.xml"), sitemap)?;
+    Ok(())
+}
+
+/// Everything the category pages of one build share.
+struct Site<'a> {
+    docs: &'a Documentation,
+    categories: &'a Categories,
+    enums: &'a Enums,
+    navigation: Navigation,
+    template_dir: &'a Path,
+    out_dir: &'a Path,
+    repo_url: Option<&'a str>,
+    /// Whether author names link to their email address, for `--link-authors`.
+    link_authors: bool,
+    /// `--sandbox-url` every function links to, `None` leaves the button out.
+    sandbox_url: Option<&'a str>,
+    highlighter: Option<Highlighter>,
+    title: &'a str,
+    /// `<img>` of the `--logo`, empty without one.
+    logo: String,
+    /// `<link>`s to the favicon and, with `--manifest`, the web app manifest.
+    favicon: String,
+    /// Theme shown before the reader picks one, empty to follow their system.
+    theme: &'static str,
+    /// Language the pages are marked as, the `--locale` or `en`.
+    lang: &'a str,
+    single_page: bool,
+    /// Whether pages and assets are written minified, for `--minify`.
+    minify: bool,
+    /// Page the "Home" breadcrumb links to.
+    home: String,
+    /// `--base-url` the pages are published under, for their canonical links.
+    base_url: Option<&'a str>,
+    /// What links to another page start with, the path of `--base-url` or empty without one.
+    base: String,
+    /// File name of each category's page without `.html`, see `page_names`.
+    pages: BTreeMap<String, String>,
+    references: BackReferences<'a>,
+}
+
+impl Site<'_> {
+    /// Writes a finished page, minified when `--minify` is set.
+    fn write_page(&self, path: &Path, html: &str) -> Result<(), Box<dyn std::error::Error>> {
+        write_asset(path, html, self.minify.then_some(minify::html))
+    }
+    
+    /// Lua code as HTML, highlighted when `--highlight` is set.
+    fn render_lua(&self, code: &str) -> Result<String, Box<dyn std::error::Error>> {
+        match &self.highlighter {
+            Some(highlighter) => highlighter.highlight_lua(code),
+            None => Ok(escape_html(code)),
+        }
+    }
+    
+    /// Id of the element a function is rendered in, from its id in `anchor_ids`.
+    fn anchor(&self, category: &str, id: &str) -> String {
+        if self.single_page {
+            single_page_anchor(category, id)
+        } else {
+            id.to_string()
+        }
+    }
+    
+    /// Link to a function from the page of `current_category`.
+    fn href(&self, current_category: &str, category: &str, name: &str) -> String {
+        if self.single_page {
+            format!("#{}", single_page_anchor(category, name))
+        } else if current_category == category {
+            format!("#{}", slugify(name))
+        } else {
+            format!("{}{}", self.base, page_href(self.page(category), name))
+        }
+    }
+    
+    /// File name of the page of `category` without `.html`.
+    fn page(&self, category: &str) -> &str {
+        &self.pages[category]
+    }
+    
+    /// Canonical link and OpenGraph URL of `page` under `--base-url`, empty without one.
+    fn canonical(&self, page: &str) -> String {
+        match self.base_url {
+            Some(base_url) => {
+                let url = escape_html(&format!("{}/{}", base_url.trim_end_matches('/'), page));
+                format!(r#"
+    <link rel="canonical" href="{}">
+    <meta property="og:url" content="{}">"#, url, url)
+            }
+            None => String::new(),
+        }
+    }
+}
+
+fn generate_category_page(
+    site: &Site,
+    category: &str,
+    functions: &[Function]
+) -> Result<(), Box<dyn std::error::Error>> {
+    let page = format!("{}.html", site.page(category));
+    let filename = site.out_dir.join(&page);
+    let template = read_file(&site.template_dir.join("category.html"))?;
+    let navigation = site.navigation.render(category);
+    
+    let mut functions_html = render_category_description(site, category);
+    functions_html.push_str(&render_enums(site, category)?);
+    functions_html.push_str(&render_functions(site, category, functions)?);
+    
+    let html = template
+        .replace("{{title}}", &escape_html(site.title))
+        .replace("{{logo}}", &site.logo)
+        .replace("{{favicon}}", &site.favicon)
+        .replace("{{theme}}", site.theme)
+        .replace("{{lang}}", &escape_html(site.lang))
+        .replace("{{category}}", &escape_html(category))
+        .replace("{{canonical}}", &site.canonical(&page))
+        .replace("{{description}}", &escape_html(&page_description(site, Some(category))))
+        .replace("{{breadcrumb}}", &render_breadcrumb(site, category))
+        .replace("{{navigation}}", &navigation)
+        .replace("{{toc}}", &render_toc(functions, category_enums(site.enums, category)))
+        .replace("{{functions}}", &functions_html);
+    
+    site.write_page(&filename, &html)
+}
+
+/// Text for the description and OpenGraph meta tags, shown in link previews: the start of the
+/// category description, or a generated line for a category without one and for the overview.
+fn page_description(site: &Site, category: Option<&str>) -> String {
+    let description = match category {
+        Some(category) => match site.categories.get(category) {
+            Some(description) => description.split("\n\n").next().unwrap_or_default().to_string(),
+            None => format!("API reference for {} in {}", category, site.title),
+        },
+        None => format!("API reference for {}, {}", site.title, count_label(site.docs.len(), "category", "categories")),
+    };
+    
+    // meta tags show plain text, so the inline markup goes
+    let plain = description.replace(['`', '*'], "").split_whitespace().collect::<Vec<_>>().join(" ");
+    if plain.chars().count() <= META_DESCRIPTION_LENGTH {
+        return plain;
+    }
+    let cut: String = plain.chars().take(META_DESCRIPTION_LENGTH).collect();
+    let cut = cut.rsplit_once(' ').map_or(cut.as_str(), |(start, _)| start);
+    format!("{}…", cut.trim_end_matches(['.', ',', ';', ':']))
+}
+
+/// Longest meta description before it is cut at a word, about what link previews show.
+const META_DESCRIPTION_LENGTH: usize = 160;
+
+fn render_breadcrumb(site: &Site, category: &str) -> String {
+    format!(r#"
+            <nav class="breadcrumb" aria-label="Breadcrumb">
+                <a href="{}" class="breadcrumb-home">Home</a>
+                <span class="breadcrumb-separator">/</span>
+                <span class="breadcrumb-current">{}</span>
+            </nav>"#, escape_html(&site.home), escape_html(category))
+}
+
+/// The `--@class` description of a category, empty when it has none.
+fn render_category_description(site: &Site, category: &str) -> String {
+    let Some(description) = site.categories.get(category) else {
+        return String::new();
+    };
+    
+    description.split("\n\n")
+        .map(|paragraph| format!(r#"
+            <p class="category-description">{}</p>"#, render_markup(paragraph)))
+        .collect()
+}
+
+/// The `--@enum` tables of a category under their own heading, empty when it has none.
+fn render_enums(site: &Site, category: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let enums = category_enums(site.enums, category);
+    if enums.is_empty() {
+        return Ok(String::new());
+    }
+    
+    let mut html = String::from(r#"
+            <h2 class="group-title">Enums</h2>"#);
+    for item in enums {
+        html.push_str(&render_enum(site, category, item)?);
+    }
+    Ok(html)
+}
+
+fn render_enum(site: &Site, category: &str, item: &Enum) -> Result<String, Box<dyn std::error::Error>> {
+    let anchor = escape_html(&site.anchor(category, &enum_anchor(&item.name)));
+    let mut html = format!(r##"
+            <div class="function enum" id="{}" data-name="{}" data-description="{}">
+                <div class="function-header">
+                    <h2 class="function-name">{}</h2>
+                    <a class="permalink" href="#{}" title="Copy link to this enum">#</a>
+                    <span class="function-id">{}.{}</span>
+                    <span class="enum-badge">enum</span>
+                </div>"##,
+        anchor,
+        escape_html(&item.name),
+        escape_html(&item.description),
+        escape_html(&item.name),
+        anchor,
+        escape_html(category),
+        escape_html(&item.name)
+    );
+    
+    for paragraph in item.description.split("\n\n").filter(|paragraph| !paragraph.is_empty()) {
+        html.push_str(&format!(r#"
+                <p class="function-description">{}</p>"#, render_markup(paragraph)));
+    }
+    
+    if item.variants.is_empty() {
+        html.push_str(r#"
+                <div class="empty-state">No values</div>"#);
+    } else {
+        html.push_str(r#"
+                <table class="enum-table">
+                    <thead>
+                        <tr><th>Name</th><th>Value</th><th>Description</th></tr>
+                    </thead>
+                    <tbody>"#);
+        for variant in &item.variants {
+            html.push_str(&format!(r#"
+                        <tr>
+                            <td class="enum-name">{}</td>
+                            <td class="enum-value"><code>{}</code></td>
+                            <td class="enum-desc">{}</td>
+                        </tr>"#,
+                escape_html(&variant.name),
+                site.render_lua(&variant.value)?,
+                render_markup(&variant.description)
+            ));
+        }
+        html.push_str(r#"
+                    </tbody>
+                </table>"#);
+    }
+    
+    html.push_str(&render_source(site, &item.source_file, item.source_line));
+    
+    html.push_str(r#"
+            </div>"#);
+    Ok(html)
+}
+
+/// A category's functions, under a heading per `--@group` when any of them has one.
+fn render_functions(
+    site: &Site,
+    category: &str,
+    functions: &[Function]
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut html = String::new();
+    for (group, members) in group_functions(functions) {
+        if let Some(group) = group {
+            html.push_str(&format!(r#"
+            <h2 class="group-title">{}</h2>"#, escape_html(group)));
+        }
+        for (func, id) in members {
+            html.push_str(&render_function(site, category, func, &id)?);
+        }
+    }
+    Ok(html)
+}
+
+/// One function's block, shared by the category pages and the single page.
+fn render_function(
+    site: &Site,
+    category: &str,
+    func: &Function,
+    id: &str
+) -> Result<String, Box<dyn std::error::Error>> {
+    let anchor = escape_html(&site.anchor(category, id));
+    let mut html = String::new();
+    let mut badges = String::new();
+    if let Some(since) = &func.since {
+        badges.push_str(&format!(r#"
+                    <span class="since">since {}</span>"#, escape_html(since)));
+    }
+    if func.private {
+        badges.push_str(r#"
+                    <span class="private">Private</span>"#);
+    }
+    if func.readonly {
+        badges.push_str(r#"
+                    <span class="readonly">Read-only</span>"#);
+    }
+    if func.deprecated.is_some() {
+        badges.push_str(r#"
+                    <span class="deprecated">Deprecated</span>"#);
+    }
+    
+    html.push_str(&format!(r##"
+            <div class="function" id="{}" data-name="{}" data-description="{}" data-deprecated="{}">
+                <div class="function-header">
+                    <h2 class="function-name">{}</h2>
+                    <a class="permalink" href="#{}" title="Copy link to this function">#</a>
+                    <span class="function-id">{}:{}</span>{}
+                </div>"##,
+        anchor,
+        escape_html(&func.name),
+        escape_html(&func.description),
+        func.deprecated.is_some(),
+        escape_html(&func.name),
+        anchor,
+        escape_html(category),
+        escape_html(&func.name),
+        badges
+    ));
+    
+    if !func.table {
+        html.push_str(&format!(r#"
+                <pre class="signature"><code class="language-lua">{}</code></pre>"#,
+            site.render_lua(&render_signature(category, func))?
+        ));
+    }
+    
+    if let Some(sandbox_url) = site.sandbox_url {
+        html.push_str(&format!(r#"
+                <a class="sandbox-link" href="{}" target="_blank" rel="noopener">Open in sandbox</a>"#,
+            escape_html(&fill_sandbox_url(sandbox_url, category, &func.name))
+        ));
+    }
+    
+    if let Some(message) = func.deprecated.as_deref().filter(|m| !m.is_empty()) {
+        html.push_str(&format!(r#"
+                <p class="deprecated-message">{}</p>"#, render_markup(message)));
+    }
+    
+    if !func.aliases.is_empty() {
+        let aliases = func.aliases.iter()
+            .map(|alias| format!("<code>{}</code>", escape_html(alias)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        html.push_str(&format!(r#"
+                <p class="function-aliases">Aliases: {}</p>"#, aliases));
+    }
+    
+    for paragraph in func.description.split("\n\n") {
+        html.push_str(&format!(r#"
+                <p class="function-description">{}</p>"#, render_markup(paragraph)));
+    }
+    
+    if !func.authors.is_empty() {
+        let authors = func.authors.iter()
+            .map(|author| render_author(author, site.link_authors))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let label = if func.authors.len() == 1 { "Author" } else { "Authors" };
+        html.push_str(&format!(r#"
+                <p class="function-authors">{}: {}</p>"#, label, authors));
+    }
+    
+    // Parameters
+    let self_param = func.self_param(&receiver(category, func));
+    if !func.params.is_empty() || self_param.is_some() {
+        html.push_str(r#"
+                <div class="params-section">
+                    <h3 class="section-title">Parameters</h3>
+                    <div class="param-list">"#);
+        
+        for param in self_param.iter().chain(&func.params) {
+            let mut item_class = String::from("param-item");
+            let mut tags = String::new();
+            if param.optional {
+                item_class.push_str(" optional");
+                tags.push_str(r#"
+                            <span class="param-optional">(optional)</span>"#);
+            }
+            if param.variadic {
+                item_class.push_str(" variadic");
+                tags.push_str(r#"
+                            <span class="param-variadic">(variadic)</span>"#);
+            }
+            if let Some(default) = &param.default {
+                tags.push_str(&format!(r#"
+                            <span class="param-default">(default: <code>{}</code>)</span>"#, escape_html(default)));
+            }
+            
+            html.push_str(&format!(r#"
+                        <div class="{}">
+                            <span class="param-name">{}</span>
+                            <span class="param-type">{}</span>{}
+                            <div class="param-desc">{}</div>
+                        </div>"#,
+                item_class,
+                escape_html(&param.display_name()),
+                render_type(&param.param_type),
+                tags,
+                render_markup(&param.description)
+            ));
+        }
+        
+        html.push_str(r#"
+                    </div>
+                </div>"#);
+    } else if !func.table {
+        html.push_str(r#"
+                <div class="params-section">
+                    <h3 class="section-title">Parameters</h3>
+                    <div class="empty-state">No parameters</div>
+                </div>"#);
+    }
+    
+    // Returns
+    if !func.returns.is_empty() {
+        html.push_str(r#"
+                <div class="returns-section">
+                    <h3 class="section-title">Returns</h3>
+                    <div class="return-list">"#);
+        
+        for ret in &func.returns {
+            html.push_str(&format!(r#"
+                        <div class="return-item">
+                            <span class="return-type">{}</span>
+                            <div class="return-desc">{}</div>
+                        </div>"#,
+                render_type(&ret.return_type),
+                render_markup(&ret.description)
+            ));
+        }
+        
+        html.push_str(r#"
+                    </div>
+                </div>"#);
+    } else if !func.table {
+        html.push_str(r#"
+                <div class="returns-section">
+                    <h3 class="section-title">Returns</h3>
+                    <div class="empty-state">No return value</div>
+                </div>"#);
+    }
+    
+    // Errors
+    if !func.errors.is_empty() {
+        html.push_str(r#"
+                <div class="errors-section">
+                    <h3 class="section-title">Errors</h3>
+                    <div class="return-list">"#);
+        
+        for error in &func.errors {
+            let error_type = if error.return_type.is_empty() {
+                String::new()
+            } else {
+                format!(r#"
+                            <span class="return-type">{}</span>"#, render_type(&error.return_type))
+            };
+            html.push_str(&format!(r#"
+                        <div class="return-item">{}
+                            <div class="return-desc">{}</div>
+                        </div>"#,
+                error_type,
+                render_markup(&error.description)
+            ));
+        }
+        
+        html.push_str(r#"
+                    </div>
+                </div>"#);
+    }
+    
+    // Overloads
+    if !func.overloads.is_empty() {
+        html.push_str(r#"
+                <div class="overloads-section">
+                    <h3 class="section-title">Overloads</h3>"#);
+        
+        for overload in &func.overloads {
+            html.push_str(&format!(r#"
+                    <pre class="signature"><code class="language-lua">{}</code></pre>"#,
+                site.render_lua(&overload_signature(category, func, overload))?
+            ));
+        }
+        
+        html.push_str(r#"
+                </div>"#);
+    }
+    
+    // Fields
+    if !func.fields.is_empty() {
+        html.push_str(r#"
+                <div class="fields-section">
+                    <h3 class="section-title">Fields</h3>
+                    <div class="field-list">"#);
+        
+        for field in &func.fields {
+            let readonly = if field.readonly {
+                r#"
+                            <span class="field-readonly">(readonly)</span>"#
+            } else {
+                ""
+            };
+            html.push_str(&format!(r#"
+                        <div class="field-item">
+                            <span class="field-name">{}</span>
+                            <span class="field-type">{}</span>{}
+                            <div class="field-desc">{}</div>
+                        </div>"#,
+                escape_html(&field.name),
+                render_type(&field.field_type),
+                readonly,
+                render_markup(&field.description)
+            ));
+        }
+        
+        html.push_str(r#"
+                    </div>
+                </div>"#);
+    }
+    
+    // Usage
+    if let Some(usage) = &func.usage {
+        html.push_str(r#"
+                <div class="usage-section">
+                    <h3 class="section-title">Usage</h3>"#);
+        
+        for paragraph in usage.split("\n\n") {
+            html.push_str(&format!(r#"
+                    <p class="usage-text">{}</p>"#, render_markup(paragraph)));
+        }
+        
+        html.push_str(r#"
+                </div>"#);
+    }
+    
+    // Examples
+    if !func.examples.is_empty() {
+        html.push_str(r#"
+                <div class="examples-section">
+                    <h3 class="section-title">Examples</h3>"#);
+        
+        for example in &func.examples {
+            html.push_str(&format!(r#"
+                    <div class="example-block">
+                        <button type="button" class="copy-button" data-code="{}" title="Copy to clipboard">Copy</button>
+                        <pre class="example"><code class="language-lua">{}</code></pre>
+                    </div>"#,
+                escape_html(example),
+                site.render_lua(example)?
+            ));
+        }
+        
+        html.push_str(r#"
+                </div>"#);
+    }
+    
+    // See also
+    if !func.see.is_empty() {
+        html.push_str(r#"
+                <div class="see-section">
+                    <h3 class="section-title">See also</h3>
+                    <ul class="see-list">"#);
+        
+        for reference in &func.see {
+            let link = match scanner::resolve_reference(site.docs, category, reference) {
+                Some((target_category, target)) => format!(r#"<a href="{}">{}</a>"#,
+                    escape_html(&site.href(category, target_category, &anchor_id(&site.docs[target_category], target))),
+                    escape_html(reference)
+                ),
+                // reported by report_unresolved_references
+                None => escape_html(reference),
+            };
+            
+            html.push_str(&format!(r#"
+                        <li class="see-item">{}</li>"#, link));
+        }
+        
+        html.push_str(r#"
+                    </ul>
+                </div>"#);
+    }
+    
+    // Referenced by
+    if let Some(sources) = site.references.get(&(category, func.name.as_str())) {
+        html.push_str(r#"
+                <div class="see-section referenced-section">
+                    <h3 class="section-title">Referenced by</h3>
+                    <ul class="see-list">"#);
+        
+        for (source_category, source) in sources {
+            html.push_str(&format!(r#"
+                        <li class="see-item"><a href="{}">{}</a></li>"#,
+                escape_html(&site.href(category, source_category, &anchor_id(&site.docs[*source_category], source))),
+                escape_html(&qualified_name(source_category, source))
+            ));
+        }
+        
+        html.push_str(r#"
+                    </ul>
+                </div>"#);
+    }
+    
+    html.push_str(&render_source(site, &func.source_file, func.source_line));
+    
+    html.push_str(r#"
+            </div>"#);
+    Ok(html)
+}
+
+/// An `--@author` as HTML, with `link` the name of `Name <email>` links to the address.
+fn render_author(author: &str, link: bool) -> String {
+    if link
+        && let Some((name, rest)) = author.split_once('<')
+        && let Some(email) = rest.trim().strip_suffix('>')
+    {
+        format!(r#"<a href="mailto:{}">{}</a>"#, escape_html(email.trim()), escape_html(name.trim()))
+    } else {
+        escape_html(author)
+    }
+}
+
+/// Where a function or enum is defined, linked when `--repo-url` is set and empty for docs.json input.
+fn render_source(site: &Site, source_file: &str, source_line: usize) -> String {
+    if source_file.is_empty() {
+        return String::new();
+    }
+    
+    let location = escape_html(&format!("{}:{}", source_file, source_line));
+    let source = match site.repo_url {
+        Some(repo_url) => format!(r#"<a href="{}">{}</a>"#,
+            escape_html(&source_url(repo_url, source_file, source_line)),
+            location
+        ),
+        None => location,
+    };
+    
+    format!(r#"
+                <div class="function-source">Defined at {}</div>"#, source)
+}
+
+/// Writes every category into one self-contained index.html.
+fn generate_single_page(site: &Site) -> Result<(), Box<dyn std::error::Error>> {
+    let html = render_single_page(site)?;
+    
+    site.write_page(&site.out_dir.join("index.html"), &html)
+}
+
+/// Every category on one page, with the stylesheet and scripts inlined.
+fn render_single_page(site: &Site) -> Result<String, Box<dyn std::error::Error>> {
+    let template = read_file(&site.template_dir.join("single.html"))?;
+    
+    let mut style = stylesheet(site.template_dir, site.highlighter.is_some())?;
+    let light_path = site.template_dir.join("style-light.css");
+    if light_path.exists() {
+        style.push_str("\n\n");
+        style.push_str(&read_file(&light_path)?);
+    }
+    let print_path = site.template_dir.join("print.css");
+    if print_path.exists() {
+        style.push_str(&format!("\n\n@media print {{\n{}\n}}", read_file(&print_path)?));
+    }
+    let script = read_file(&site.template_dir.join("search.js"))?;
+    
+    let entries = search_entries(site.docs, |category, name| format!("#{}", single_page_anchor(category, name)));
+    // keep a "</script>" inside a description from closing the inline script
+    let search_index = serde_json::to_string(&entries)?.replace("</", "<\\/");
+    
+    let sections = site.docs.par_iter()
+        .map(|(category, functions)| {
+            let mut section = format!(r#"
+            <section class="category" id="{}">
+                <h1 class="page-title">{}</h1>{}"#,
+                escape_html(&slugify(category)),
+                escape_html(category),
+                render_category_description(site, category)
+            );
+            section.push_str(&render_enums(site, category).map_err(|error| error.to_string())?);
+            section.push_str(&render_functions(site, category, functions).map_err(|error| error.to_string())?);
+            section.push_str(r#"
+            </section>"#);
+            Ok(section)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    
+    let html = template
+        .replace("{{style}}", &style)
+        .replace("{{title}}", &escape_html(site.title))
+        .replace("{{canonical}}", &site.canonical(""))
+        .replace("{{description}}", &escape_html(&page_description(site, None)))
+        .replace("{{logo}}", &site.logo)
+        .replace("{{favicon}}", &site.favicon)
+        .replace("{{theme}}", site.theme)
+        .replace("{{lang}}", &escape_html(site.lang))
+        .replace("{{script}}", &script)
+        .replace("{{search_index}}", &search_index)
+        .replace("{{navigation}}", &site.navigation.render(""))
+        .replace("{{content}}", &if sections.is_empty() { String::from(NO_DOCUMENTATION) } else { sections.concat() });
+    
+    Ok(html)
+}
+
+/// Shown in place of the category list when nothing was documented.
+const NO_DOCUMENTATION: &str = r#"
+            <p class="empty-state">No documentation found. Add <code>--@</code> doc comments above your functions and generate again.</p>"#;
+
+/// Writes index.html as an overview linking to every category page.
+fn generate_landing_page(site: &Site) -> Result<(), Box<dyn std::error::Error>> {
+    let template = read_file(&site.template_dir.join("category.html"))?;
+    
+    let mut categories_html = String::from(r#"
+            <div class="category-list">"#);
+    for (category, functions) in site.docs {
+        let enums = category_enums(site.enums, category);
+        let mut count = count_label(functions.len(), "function", "functions");
+        if !enums.is_empty() {
+            count = if functions.is_empty() {
+                count_label(enums.len(), "enum", "enums")
+            } else {
+                format!("{}, {}", count, count_label(enums.len(), "enum", "enums"))
+            };
+        }
+        
+        let summary = match site.categories.get(category) {
+            Some(description) => format!(r#"
+                    <p class="category-summary">{}</p>"#,
+                render_markup(description.split("\n\n").next().unwrap_or_default())
+            ),
+            None => String::new(),
+        };
+        
+        categories_html.push_str(&format!(r#"
+                <a class="category-card" href="{}{}.html">
+                    <h2 class="category-name">{}</h2>{}
+                    <span class="category-count">{}</span>
+                </a>"#,
+            escape_html(&site.base),
+            escape_html(site.page(category)),
+            escape_html(category),
+            summary,
+            count
+        ));
+    }
+    categories_html.push_str(r#"
+            </div>"#);
+    let mut links = format!(r#"<a href="{}index-az.html">A–Z index</a>"#, escape_html(&site.base));
+    if site.docs.values().flatten().any(|func| !func.authors.is_empty()) {
+        links.push_str(&format!(r#" · <a href="{}authors.html">Authors</a>"#, escape_html(&site.base)));
+    }
+    categories_html.push_str(&format!(r#"
+            <p class="index-links">{}</p>"#, links));
+    if site.docs.is_empty() {
+        categories_html = String::from(NO_DOCUMENTATION);
+    }
+    
+    let html = template
+        .replace("{{title}}", &escape_html(site.title))
+        .replace("{{logo}}", &site.logo)
+        .replace("{{favicon}}", &site.favicon)
+        .replace("{{theme}}", site.theme)
+        .replace("{{lang}}", &escape_html(site.lang))
+        .replace("{{category}}", "Overview")
+        .replace("{{canonical}}", &site.canonical(""))
+        .replace("{{description}}", &escape_html(&page_description(site, None)))
+        .replace("{{breadcrumb}}", "")
+        .replace("{{navigation}}", &site.navigation.render(""))
+        .replace("{{toc}}", "")
+        .replace("{{functions}}", &categories_html);
+    
+    site.write_page(&site.out_dir.join("index.html"), &html)
+}
+
+/// A function in the A–Z index: its category, itself and its anchor id.
+type IndexEntry<'a> = (&'a str, &'a Function, String);
+
+/// Writes index-az.html, every function sorted by name like the index of a book, under a
+/// heading for each first letter. Names starting with anything but a letter are listed under `#`.
+fn generate_alphabetical_index(site: &Site) -> Result<(), Box<dyn std::error::Error>> {
+    let mut entries: Vec<IndexEntry> = site.docs.iter()
+        .flat_map(|(category, functions)| {
+            functions.iter()
+                .zip(anchor_ids(functions))
+                .map(move |(func, id)| (category.as_str(), func, id))
+        })
+        .collect();
+    entries.sort_by(|(a_category, a, _), (b_category, b, _)| {
+        a.name.to_lowercase().cmp(&b.name.to_lowercase()).then_with(|| a_category.cmp(b_category))
+    });
+    
+    let mut letters: Vec<(String, Vec<IndexEntry>)> = Vec::new();
+    for entry in entries {
+        let letter = match entry.1.name.chars().next() {
+            Some(first) if first.is_alphabetic() => first.to_uppercase().collect(),
+            _ => String::from("#"),
+        };
+        match letters.last_mut() {
+            Some((current, group)) if *current == letter => group.push(entry),
+            _ => letters.push((letter, vec![entry])),
+        }
+    }
+    
+    let letter_id = |letter: &str| if letter == "#" { String::from("letter-other") } else { format!("letter-{}", slugify(letter)) };
+    let jump = letters.iter()
+        .map(|(letter, _)| format!(r##"<a href="#{}">{}</a>"##, letter_id(letter), escape_html(letter)))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut index_html = format!(r#"
+            <nav class="letter-jump">{}</nav>"#, jump);
+    
+    for (letter, group) in &letters {
+        index_html.push_str(&format!(r#"
+            <div class="letter-group" id="{}">
+                <h2 class="group-title">{}</h2>
+                <ul class="see-list">"#, letter_id(letter), escape_html(letter)));
+        
+        for (category, func, id) in group {
+            index_html.push_str(&format!(r#"
+                    <li class="see-item"><a href="{}">{}</a> <span class="index-category">{}</span></li>"#,
+                escape_html(&site.href("", category, id)),
+                escape_html(&func.name),
+                escape_html(category)
+            ));
+        }
+        
+        index_html.push_str(r#"
+                </ul>
+            </div>"#);
+    }
+    if site.docs.is_empty() {
+        index_html = String::from(NO_DOCUMENTATION);
+    }
+    
+    let template = read_file(&site.template_dir.join("category.html"))?;
+    let html = template
+        .replace("{{title}}", &escape_html(site.title))
+        .replace("{{logo}}", &site.logo)
+        .replace("{{favicon}}", &site.favicon)
+        .replace("{{theme}}", site.theme)
+        .replace("{{lang}}", &escape_html(site.lang))
+        .replace("{{category}}", "A–Z index")
+        .replace("{{canonical}}", &site.canonical("index-az.html"))
+        .replace("{{description}}", &escape_html(&format!("Every function of the {} API from A to Z", site.title)))
+        .replace("{{breadcrumb}}", &render_breadcrumb(site, "A–Z index"))
+        .replace("{{navigation}}", &site.navigation.render(""))
+        .replace("{{toc}}", "")
+        .replace("{{functions}}", &index_html);
+    
+    site.write_page(&site.out_dir.join("index-az.html"), &html)
+}
+
+/// Writes authors.html, listing what each `--@author` wrote. Nothing is written when no function names one.
+fn generate_authors_page(site: &Site) -> Result<(), Box<dyn std::error::Error>> {
+    let mut authors: BTreeMap<&str, Vec<(&str, &Function)>> = BTreeMap::new();
+    for (category, functions) in site.docs {
+        for func in functions {
+            for author in &func.authors {
+                authors.entry(author).or_default().push((category, func));
+            }
+        }
+    }
+    if authors.is_empty() {
+        return Ok(());
+    }
+    
+    let template = read_file(&site.template_dir.join("category.html"))?;
+    let mut authors_html = String::from(r#"
+            <div class="author-list">"#);
+    for (author, functions) in &authors {
+        authors_html.push_str(&format!(r#"
+                <div class="author">
+                    <h2 class="author-name">{}</h2>
+                    <ul class="see-list">"#, render_author(author, site.link_authors)));
+        
+        for (category, func) in functions {
+            authors_html.push_str(&format!(r#"
+                        <li class="see-item"><a href="{}">{}</a></li>"#,
+                escape_html(&site.href("", category, &anchor_id(&site.docs[*category], func))),
+                escape_html(&qualified_name(category, func))
+            ));
+        }
+        
+        authors_html.push_str(r#"
+                    </ul>
+                </div>"#);
+    }
+    authors_html.push_str(r#"
+            </div>"#);
+    
+    let html = template
+        .replace("{{title}}", &escape_html(site.title))
+        .replace("{{logo}}", &site.logo)
+        .replace("{{favicon}}", &site.favicon)
+        .replace("{{theme}}", site.theme)
+        .replace("{{lang}}", &escape_html(site.lang))
+        .replace("{{category}}", "Authors")
+        .replace("{{canonical}}", &site.canonical("authors.html"))
+        .replace("{{description}}", &escape_html(&format!("Authors of the {} API", site.title)))
+        .replace("{{breadcrumb}}", &render_breadcrumb(site, "Authors"))
+        .replace("{{navigation}}", &site.navigation.render(""))
+        .replace("{{toc}}", "")
+        .replace("{{functions}}", &authors_html);
+    
+    site.write_page(&site.out_dir.join("authors.html"), &html)
+}
+
+/// `1 function`, `2 functions`.
+fn count_label(count: usize, singular: &str, plural: &str) -> String {
+    if count == 1 {
+        format!("1 {}", singular)
+    } else {
+        format!("{} {}", count, plural)
+    }
+}
+
+fn generate_index_redirect(site: &Site, first_category: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let template = read_file(&site.template_dir.join("index.html"))?;
+    
+    let html = template
+        .replace("{{title}}", &escape_html(site.title))
+        .replace("{{favicon}}", &site.favicon)
+        .replace("{{canonical}}", &site.canonical(&format!("{}.html", site.page(first_category))))
+        .replace("{{first_category}}", &escape_html(&format!("{}{}", site.base, site.page(first_category))));
+    
+    site.write_page(&site.out_dir.join("index.html"), &html)
+}
+
+/// Name a function is called by, e.g. `Player.Kick`, or `Player:Kick` for a colon method.
+fn qualified_name(category: &str, func: &Function) -> String {
+    let name = if category == "Global" {
+        func.name.clone()
+    } else {
+        format!("{}.{}", category, func.name)
+    };
+    
+    match name.rsplit_once('.') {
+        Some((receiver, method)) if func.is_method => format!("{}:{}", receiver, method),
+        _ => name,
+    }
+}
+
+/// What `self` refers to in a colon method, `Player` for `Player:Kick`.
+fn receiver(category: &str, func: &Function) -> String {
+    let name = qualified_name(category, func);
+    match name.rsplit_once(':') {
+        Some((receiver, _)) => receiver.to_string(),
+        None => category.to_string(),
+    }
+}
+
+/// One-line signature such as `Player.Kick(target: Player, reason?: string): boolean`.
+fn render_signature(category: &str, func: &Function) -> String {
+    let params = func.params.iter()
+        // the colon already passes self
+        .filter(|param| !(func.is_method && param.name == "self"))
+        .map(|param| {
+            let mut name = param.display_name();
+            if param.optional {
+                name.push('?');
+            }
+            if param.param_type.is_empty() {
+                name
+            } else {
+                format!("{}: {}", name, param.param_type)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    
+    let mut signature = format!("{}({})", qualified_name(category, func), params);
+    
+    let returns = func.returns.iter()
+        .map(|ret| ret.return_type.as_str())
+        .filter(|return_type| !return_type.is_empty())
+        .collect::<Vec<_>>();
+    if !returns.is_empty() {
+        signature.push_str(": ");
+        signature.push_str(&returns.join(", "));
+    }
+    
+    signature
+}
+
+/// An `--@overload` as a full signature, `(x: number): string` and `fun(x: number): string`
+/// get the function's name in front, anything else is shown as written.
+fn overload_signature(category: &str, func: &Function, overload: &str) -> String {
+    let arguments = overload.strip_prefix("fun").unwrap_or(overload);
+    if arguments.starts_with('(') {
+        format!("{}{}", qualified_name(category, func), arguments)
+    } else {
+        overload.to_string()
+    }
+}
+
+/// Link to the line a function is documented at, e.g. a GitHub blob URL. Each folder and the
+/// file name are encoded, so a space or `#` in them does not break the link.
+fn source_url(repo_url: &str, source_file: &str, source_line: usize) -> String {
+    let path = source_file.split('/').map(percent_encode).collect::<Vec<_>>().join("/");
+    format!("{}/{}#L{}", repo_url.trim_end_matches('/'), path, source_line)
+}
+
+/// The `--sandbox-url` of a function, with its name and category encoded into the placeholders.
+fn fill_sandbox_url(sandbox_url: &str, category: &str, name: &str) -> String {
+    sandbox_url
+        .replace("{name}", &percent_encode(name))
+        .replace("{category}", &percent_encode(category))
+}
+
+/// Escapes everything but letters, digits and `-_.~` so `text` can go anywhere in a URL.
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Renders each member of a union type like `string|number` as its own token.
+fn render_type(type_str: &str) -> String {
+    if type_str.is_empty() {
+        return String::new();
+    }
+    
+    scanner::split_top_level(type_str, '|')
+        .iter()
+        .map(|token| format!(r#"<span class="type-token {}">{}</span>"#, type_class(token), escape_html(token)))
+        .collect::<Vec<_>>()
+        .join(r#"<span class="type-separator">|</span>"#)
+}
+
+/// Class coloring a type by its base Lua type, `type-custom` for classes like `Player`.
+/// Optional, array and generic types count as their base (`string?`, `number[]`, `table<K, V>`),
+/// a union takes the first member that is a known type.
+fn type_class(type_str: &str) -> &'static str {
+    scanner::split_top_level(type_str, '|')
+        .iter()
+        .find_map(|member| {
+            let base = member.trim().trim_end_matches('?');
+            let base = base.split(['<', '[', '(']).next().unwrap_or(base);
+            match base {
+                "string" => Some("type-string"),
+                "number" | "integer" => Some("type-number"),
+                "boolean" | "bool" => Some("type-boolean"),
+                "table" => Some("type-table"),
+                "function" | "fun" => Some("type-function"),
+                "nil" | "void" => Some("type-nil"),
+                "any" | "userdata" | "thread" => Some("type-any"),
+                _ => None,
+            }
+        })
+        .unwrap_or("type-custom")
+}
+
+/// Escapes text and turns the inline markup of descriptions into HTML:
+/// `` `code` ``, `**bold**` and `*italic*`. Runs on the escaped text so markup cannot inject tags.
+fn render_markup(text: &str) -> String {
+    let bold_regex = Regex::new(r"\*\*([^*]+)\*\*").unwrap();
+    let italic_regex = Regex::new(r"\*([^*\s](?:[^*]*[^*\s])?)\*").unwrap();
+    
+    let escaped = escape_html(text);
+    let parts: Vec<&str> = escaped.split('`').collect();
+    
+    let mut html = String::with_capacity(escaped.len());
+    for (i, part) in parts.iter().enumerate() {
+        // an unmatched backtick is shown as is
+        let unmatched = i == parts.len() - 1 && parts.len().is_multiple_of(2);
+        if i % 2 == 1 && !unmatched {
+            html.push_str(&format!("<code>{}</code>", part));
+        } else {
+            if unmatched {
+                html.push('`');
+            }
+            let bold = bold_regex.replace_all(part, "<strong>$1</strong>");
+            html.push_str(&italic_regex.replace_all(&bold, "<em>$1</em>"));
+        }
+    }
+    html
+}
+
+/// Escapes text for use in HTML content and quoted attribute values.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn source_url_encodes_each_path_segment() {
+        assert_eq!(
+            source_url("https://github.com/me/repo/blob/main/", "lua/my folder/#1 player.lua", 12),
+            "https://github.com/me/repo/blob/main/lua/my%20folder/%231%20player.lua#L12"
+        );
+    }
+    
+    #[test]
+    fn base_path_is_empty_at_the_root_of_a_domain() {
+        assert_eq!(base_path("https://example.com"), "");
+        assert_eq!(base_path("https://example.com/"), "");
+    }
+    
+    #[test]
+    fn base_path_keeps_one_slash_around_the_subpath() {
+        assert_eq!(base_path("https://example.com/api"), "/api/");
+        assert_eq!(base_path("https://example.com/api/"), "/api/");
+        assert_eq!(base_path("https://example.com/docs/api/v2"), "/docs/api/v2/");
+    }
+}
@@ -0,0 +1,69 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// How much is logged, set once from `--quiet` and `--verbose`. Warnings, errors and the
+/// `[ OK ]` results are always printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// Only results, warnings and errors.
+    Quiet,
+    /// Also `[ INFO ]` lines about the build as a whole.
+    Normal,
+    /// Also a line for every scanned file and everything found in it.
+    Verbose,
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Normal as u8);
+
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// Whether lines of `level` are printed.
+pub fn enabled(level: Level) -> bool {
+    LEVEL.load(Ordering::Relaxed) >= level as u8
+}
+
+/// `[ INFO ]` about the build as a whole, left out with `--quiet`.
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Normal) {
+            println!("[ INFO ] {}", format_args!($($arg)*));
+        }
+    };
+}
+
+/// `[ INFO ]` about a single file or what was found in it, only printed with `--verbose`.
+macro_rules! detail {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Verbose) {
+            println!("[ INFO ] {}", format_args!($($arg)*));
+        }
+    };
+}
+
+macro_rules! ok {
+    ($($arg:tt)*) => {
+        println!("[ OK ] {}", format_args!($($arg)*))
+    };
+}
+
+/// `[ OK ]` on stderr, for commands whose stdout is their output like `--diff`.
+macro_rules! ok_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[ OK ] {}", format_args!($($arg)*))
+    };
+}
+
+macro_rules! warning {
+    ($($arg:tt)*) => {
+        eprintln!("[ WARN ] {}", format_args!($($arg)*))
+    };
+}
+
+macro_rules! error {
+    ($($arg:tt)*) => {
+        eprintln!("[ ERROR ] {}", format_args!($($arg)*))
+    };
+}
+
+pub(crate) use {detail, error, info, ok, ok_stderr, warning};
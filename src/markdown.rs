@@ -0,0 +1,417 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::navigation::{category_enums, page_names, unique_slug};
+use crate::scanner::{self, BackReferences, Categories, Documentation, Enum, Enums, Function};
+
+/// Writes one markdown file per category plus a SUMMARY.md for mdBook.
+pub fn generate_markdown(
+    docs: &Documentation,
+    categories: &Categories,
+    enums: &Enums,
+    out_dir: &Path,
+    repo_url: Option<&str>
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut summary = String::from("# Summary\n\n");
+    let mut book = Book {
+        docs,
+        categories,
+        enums,
+        // SUMMARY.md is the same file as summary.md on a case-insensitive file system
+        pages: page_names(docs, &["summary"]),
+        references: scanner::back_references(docs),
+        repo_url,
+        anchors: BTreeMap::new(),
+    };
+    // a heading's id depends on every heading before it in the file, so they are read off a first rendering
+    book.anchors = docs.iter()
+        .map(|(category, functions)| {
+            let markdown = render_category(&book, category, functions);
+            (category.clone(), function_anchors(&markdown, category_enums(enums, category).len()))
+        })
+        .collect();
+    
+    for (category, functions) in docs {
+        let filename = format!("{}.md", book.pages[category]);
+        let markdown = render_category(&book, category, functions);
+        fs::write(out_dir.join(&filename), markdown)?;
+        summary.push_str(&format!("- [{}]({})\n", escape_markdown(category), filename));
+    }
+    
+    fs::write(out_dir.join("SUMMARY.md"), summary)?;
+    Ok(())
+}
+
+/// Everything the markdown files of one build share.
+struct Book<'a> {
+    docs: &'a Documentation,
+    categories: &'a Categories,
+    enums: &'a Enums,
+    /// File name of each category's file without `.md`, see `page_names`.
+    pages: BTreeMap<String, String>,
+    references: BackReferences<'a>,
+    repo_url: Option<&'a str>,
+    /// Heading ids of each category's functions, in the order of its file.
+    anchors: BTreeMap<String, Vec<String>>,
+}
+
+impl Book<'_> {
+    /// Id of the heading of `func`, one of the functions of `category`.
+    fn anchor(&self, category: &str, func: &Function) -> String {
+        self.docs[category].iter()
+            .position(|other| std::ptr::eq(other, func))
+            .and_then(|index| self.anchors.get(category)?.get(index).cloned())
+            .unwrap_or_else(|| heading_id(&func.name))
+    }
+}
+
+fn render_category(book: &Book, category: &str, functions: &[Function]) -> String {
+    let mut markdown = format!("# {}\n\n", escape_markdown(category));
+    if let Some(description) = book.categories.get(category) {
+        markdown.push_str(&format!("{}\n\n", escape_prose(description)));
+    }
+    
+    for item in category_enums(book.enums, category) {
+        markdown.push_str(&render_enum(item, book.repo_url));
+    }
+    
+    for func in functions {
+        markdown.push_str(&format!("## {}\n\n", escape_markdown(&func.name)));
+        if !func.table {
+            markdown.push_str(&format!("```lua\n{}\n```\n\n", crate::render_signature(category, func)));
+        }
+        
+        if let Some(since) = &func.since {
+            markdown.push_str(&format!("*Since {}*\n\n", escape_markdown(since)));
+        }
+        
+        if func.private {
+            markdown.push_str("*Private*\n\n");
+        }
+        
+        if func.readonly {
+            markdown.push_str("*Read-only*\n\n");
+        }
+        
+        if !func.aliases.is_empty() {
+            let aliases = func.aliases.iter()
+                .map(|alias| format!("`{}`", alias))
+                .collect::<Vec<_>>()
+                .join(", ");
+            markdown.push_str(&format!("*Aliases: {}*\n\n", aliases));
+        }
+        
+        if let Some(message) = &func.deprecated {
+            if message.is_empty() {
+                markdown.push_str("> **Deprecated**\n\n");
+            } else {
+                markdown.push_str(&format!("> **Deprecated:** {}\n\n", escape_prose(message)));
+            }
+        }
+        
+        if !func.description.is_empty() {
+            markdown.push_str(&format!("{}\n\n", escape_prose(&func.description)));
+        }
+        
+        if !func.authors.is_empty() {
+            let label = if func.authors.len() == 1 { "Author" } else { "Authors" };
+            let authors = func.authors.iter().map(|author| escape_markdown(author)).collect::<Vec<_>>().join(", ");
+            markdown.push_str(&format!("*{}: {}*\n\n", label, authors));
+        }
+        
+        if !func.table {
+            markdown.push_str("### Parameters\n\n");
+            let self_param = func.self_param(&crate::receiver(category, func));
+            if func.params.is_empty() && self_param.is_none() {
+                markdown.push_str("*No parameters*\n\n");
+            } else {
+                markdown.push_str("| Name | Type | Description |\n| --- | --- | --- |\n");
+                for param in self_param.iter().chain(&func.params) {
+                    let mut tags = String::new();
+                    if param.optional {
+                        tags.push_str(" *(optional)*");
+                    }
+                    if param.variadic {
+                        tags.push_str(" *(variadic)*");
+                    }
+                    if let Some(default) = &param.default {
+                        tags.push_str(&format!(" *(default: {})*", code_table_cell(default)));
+                    }
+                    markdown.push_str(&format!(
+                        "| {}{} | {} | {} |\n",
+                        code_table_cell(&param.display_name()),
+                        tags,
+                        code_table_cell(&param.param_type),
+                        escape_table_cell(&param.description)
+                    ));
+                }
+                markdown.push('\n');
+            }
+            
+            markdown.push_str("### Returns\n\n");
+            if func.returns.is_empty() {
+                markdown.push_str("*No return value*\n\n");
+            } else {
+                markdown.push_str("| Type | Description |\n| --- | --- |\n");
+                for ret in &func.returns {
+                    markdown.push_str(&format!(
+                        "| {} | {} |\n",
+                        code_table_cell(&ret.return_type),
+                        escape_table_cell(&ret.description)
+                    ));
+                }
+                markdown.push('\n');
+            }
+        }
+        
+        if !func.errors.is_empty() {
+            markdown.push_str("### Errors\n\n");
+            markdown.push_str("| Type | Description |\n| --- | --- |\n");
+            for error in &func.errors {
+                markdown.push_str(&format!(
+                    "| {} | {} |\n",
+                    code_table_cell(&error.return_type),
+                    escape_table_cell(&error.description)
+                ));
+            }
+            markdown.push('\n');
+        }
+        
+        if !func.overloads.is_empty() {
+            let overloads = func.overloads.iter()
+                .map(|overload| crate::overload_signature(category, func, overload))
+                .collect::<Vec<_>>()
+                .join("\n");
+            markdown.push_str(&format!("### Overloads\n\n```lua\n{}\n```\n\n", overloads));
+        }
+        
+        if !func.fields.is_empty() {
+            markdown.push_str("### Fields\n\n");
+            markdown.push_str("| Name | Type | Description |\n| --- | --- | --- |\n");
+            for field in &func.fields {
+                markdown.push_str(&format!(
+                    "| {}{} | {} | {} |\n",
+                    code_table_cell(&field.name),
+                    if field.readonly { " *(readonly)*" } else { "" },
+                    code_table_cell(&field.field_type),
+                    escape_table_cell(&field.description)
+                ));
+            }
+            markdown.push('\n');
+        }
+        
+        if let Some(usage) = &func.usage {
+            markdown.push_str(&format!("### Usage\n\n{}\n\n", escape_prose(usage)));
+        }
+        
+        if !func.examples.is_empty() {
+            markdown.push_str("### Examples\n\n");
+            for example in &func.examples {
+                markdown.push_str(&format!("```lua\n{}\n```\n\n", example));
+            }
+        }
+        
+        if !func.see.is_empty() {
+            markdown.push_str("### See also\n\n");
+            for reference in &func.see {
+                match scanner::resolve_reference(book.docs, category, reference) {
+                    Some((target_category, target)) => markdown.push_str(&format!(
+                        "- [{}]({}.md#{})\n",
+                        escape_markdown(reference),
+                        book.pages[target_category],
+                        book.anchor(target_category, target)
+                    )),
+                    None => markdown.push_str(&format!("- {}\n", escape_markdown(reference))),
+                }
+            }
+            markdown.push('\n');
+        }
+        
+        if let Some(sources) = book.references.get(&(category, func.name.as_str())) {
+            markdown.push_str("### Referenced by\n\n");
+            for (source_category, source) in sources {
+                markdown.push_str(&format!(
+                    "- [{}]({}.md#{})\n",
+                    escape_markdown(&crate::qualified_name(source_category, source)),
+                    book.pages[*source_category],
+                    book.anchor(source_category, source)
+                ));
+            }
+            markdown.push('\n');
+        }
+        
+        if !func.source_file.is_empty() {
+            match book.repo_url {
+                Some(repo_url) => markdown.push_str(&format!(
+                    "*Defined at [{}]({})*\n\n",
+                    escape_markdown(&func.location()),
+                    crate::source_url(repo_url, &func.source_file, func.source_line)
+                )),
+                None => markdown.push_str(&format!("*Defined at {}*\n\n", escape_markdown(&func.location()))),
+            }
+        }
+    }
+    
+    format!("{}\n", markdown.trim_end())
+}
+
+fn render_enum(item: &Enum, repo_url: Option<&str>) -> String {
+    let mut markdown = format!("## {}\n\n*Enum*\n\n", escape_markdown(&item.name));
+    if !item.description.is_empty() {
+        markdown.push_str(&format!("{}\n\n", escape_prose(&item.description)));
+    }
+    
+    if item.variants.is_empty() {
+        markdown.push_str("*No values*\n\n");
+    } else {
+        markdown.push_str("| Name | Value | Description |\n| --- | --- | --- |\n");
+        for variant in &item.variants {
+            markdown.push_str(&format!(
+                "| {} | {} | {} |\n",
+                code_table_cell(&variant.name),
+                code_table_cell(&variant.value),
+                escape_table_cell(&variant.description)
+            ));
+        }
+        markdown.push('\n');
+    }
+    
+    if !item.source_file.is_empty() {
+        let location = format!("{}:{}", item.source_file, item.source_line);
+        match repo_url {
+            Some(repo_url) => markdown.push_str(&format!(
+                "*Defined at [{}]({})*\n\n",
+                escape_markdown(&location),
+                crate::source_url(repo_url, &item.source_file, item.source_line)
+            )),
+            None => markdown.push_str(&format!("*Defined at {}*\n\n", escape_markdown(&location))),
+        }
+    }
+    
+    markdown
+}
+
+/// Ids of the function headings in the markdown of a category, the level two headings after
+/// the ones of its `enums`. Every heading counts towards the `-1`, `-2`, ... of a repeated id.
+fn function_anchors(markdown: &str, enums: usize) -> Vec<String> {
+    let mut ids = Vec::new();
+    let mut functions = Vec::new();
+    let mut in_code = false;
+    for line in markdown.lines() {
+        if line.starts_with("```") {
+            in_code = !in_code;
+        }
+        let Some((level, text)) = line.split_once(' ') else {
+            continue;
+        };
+        if in_code || level.is_empty() || level.len() > 6 || level.chars().any(|c| c != '#') {
+            continue;
+        }
+        
+        let id = unique_slug(heading_id(&unescape_markdown(text)), &ids);
+        if level == "##" {
+            functions.push(id.clone());
+        }
+        ids.push(id);
+    }
+    functions.split_off(enums.min(functions.len()))
+}
+
+/// Id GitHub and mdBook give a heading: lowercased, with spaces turned into `-` and every
+/// character but letters, digits, `_` and `-` left out, so `Http.Get` becomes `httpget`.
+fn heading_id(text: &str) -> String {
+    text.trim()
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            '_' | '-' => Some(c),
+            _ if c.is_alphanumeric() => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The text `escape_markdown` was given for `escaped`.
+fn unescape_markdown(escaped: &str) -> String {
+    let mut text = String::with_capacity(escaped.len());
+    let mut chars = escaped.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => text.extend(chars.next()),
+            _ => text.push(c),
+        }
+    }
+    text.replace("&lt;", "<").replace("&gt;", ">")
+}
+
+/// Escapes characters that would otherwise be read as markdown or inline HTML.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '*' | '_' | '[' | ']' | '#' | '`' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escapes description text but keeps its inline markup (`` `code` ``, `**bold**`, `*italic*`).
+fn escape_prose(text: &str) -> String {
+    if text.matches('`').count() % 2 == 1 {
+        return escape_markdown(text).replace("\\*", "*");
+    }
+    
+    text.split('`')
+        .enumerate()
+        .map(|(i, part)| if i % 2 == 1 {
+            // code spans are shown literally
+            part.to_string()
+        } else {
+            escape_markdown(part).replace("\\*", "*")
+        })
+        .collect::<Vec<_>>()
+        .join("`")
+}
+
+/// Escapes text for a table cell, where a bare pipe would start a new column.
+fn escape_table_cell(text: &str) -> String {
+    escape_prose(text)
+        .replace('|', "\\|")
+        .replace('\n', "<br>")
+}
+
+/// Wraps text in a code span for a table cell, pipes still need escaping inside code spans.
+fn code_table_cell(text: &str) -> String {
+    if text.is_empty() {
+        return String::new();
+    }
+    format!("`{}`", text.replace('|', "\\|"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn heading_ids_follow_github_and_mdbook() {
+        assert_eq!(heading_id("Http.Get"), "httpget");
+        assert_eq!(heading_id("get_name"), "get_name");
+        assert_eq!(heading_id("See also"), "see-also");
+        assert_eq!(heading_id(&unescape_markdown(&escape_markdown("Vec<T>.__index"))), "vect__index");
+    }
+    
+    #[test]
+    fn function_anchors_count_every_heading_of_the_file() {
+        let markdown = "# Player\n\n## Team\n\n## Kick\n\n### Examples\n\n```lua\n# not a heading\n```\n\n\
+            ## Kick\n\n## Examples\n\n## Http.Get\n";
+        assert_eq!(function_anchors(markdown, 1), ["kick", "kick-1", "examples-1", "httpget"]);
+    }
+}
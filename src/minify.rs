@@ -0,0 +1,36 @@
+use minify_html::Cfg;
+
+/// Minifies a finished page along with the styles and scripts inlined into it. Whitespace
+/// inside `<pre>` is kept as is, so examples and signatures stay laid out the way they were written.
+pub fn html(html: &str) -> String {
+    String::from_utf8_lossy(&minify_html::minify(html.as_bytes(), &config())).into_owned()
+}
+
+/// Minifies a stylesheet by running it through the same minifier as an inline `<style>`.
+pub fn css(css: &str) -> String {
+    inline(css, "<style>", "</style>")
+}
+
+/// Minifies a script by running it through the same minifier as an inline `<script>`. Scripts
+/// that fail to parse are kept unminified rather than broken.
+pub fn js(js: &str) -> String {
+    inline(js, "<script>", "</script>")
+}
+
+fn inline(code: &str, open: &str, close: &str) -> String {
+    let minified = html(&format!("{}{}{}", open, code, close));
+    minified.strip_prefix(open)
+        .and_then(|minified| minified.strip_suffix(close))
+        .map_or_else(|| code.to_string(), str::to_string)
+}
+
+fn config() -> Cfg {
+    Cfg {
+        minify_css: true,
+        minify_js: true,
+        // the pages keep every closing tag, so whatever reads them doesn't need to infer them
+        keep_closing_tags: true,
+        keep_html_and_head_opening_tags: true,
+        ..Cfg::default()
+    }
+}
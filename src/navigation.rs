@@ -0,0 +1,369 @@
+use std::collections::BTreeMap;
+
+use crate::escape_html;
+use crate::scanner::{Documentation, Enum, Enums, Function};
+
+/// Sidebar markup shared by all pages, built once. Every category section is kept twice:
+/// with in-page anchors for its own page and with cross-page links for every other page.
+/// Sections are `<details>` so they fold without any script, on a category page only its own
+/// section starts open.
+pub struct Navigation {
+    sections: Vec<NavSection>,
+}
+
+struct NavSection {
+    category: String,
+    local: String,
+    remote: String,
+}
+
+impl Navigation {
+    /// Links to other pages start with `base`, see `base_path`, and go to the `page_names` in `pages`.
+    pub fn new(docs: &Documentation, enums: &Enums, pages: &BTreeMap<String, String>, base: &str) -> Self {
+        let sections = docs.iter()
+            .map(|(category, functions)| {
+                let enums = category_enums(enums, category);
+                NavSection {
+                    category: category.clone(),
+                    local: render_section(category, functions, enums, |name| format!("#{}", slugify(name))),
+                    remote: render_section(category, functions, enums, |name| format!("{}{}", base, page_href(&pages[category], name))),
+                }
+            })
+            .collect();
+        Self { sections }
+    }
+    
+    /// Sidebar for `--single-page`, where every link is an anchor on the same page.
+    pub fn single_page(docs: &Documentation, enums: &Enums) -> Self {
+        let sections = docs.iter()
+            .map(|(category, functions)| {
+                let section = render_section(category, functions, category_enums(enums, category), |name| {
+                    format!("#{}", single_page_anchor(category, name))
+                });
+                NavSection { category: category.clone(), local: section.clone(), remote: section }
+            })
+            .collect();
+        Self { sections }
+    }
+    
+    /// Sidebar for the page of `current_category`, or with every section open for an empty one.
+    pub fn render(&self, current_category: &str) -> String {
+        self.sections.iter()
+            .map(|section| {
+                let (open, body) = if section.category == current_category {
+                    (" open", &section.local)
+                } else if current_category.is_empty() {
+                    (" open", &section.remote)
+                } else {
+                    ("", &section.remote)
+                };
+                format!(r#"
+                <details class="nav-section"{}>{}
+                </details>"#, open, body)
+            })
+            .collect()
+    }
+}
+
+/// The enums of `category`, empty when it has none.
+pub fn category_enums<'a>(enums: &'a Enums, category: &str) -> &'a [Enum] {
+    enums.get(category).map_or(&[], Vec::as_slice)
+}
+
+/// Id of an enum on its category page, prefixed so it never matches a function of the same name.
+pub fn enum_anchor(name: &str) -> String {
+    format!("enum-{}", slugify(name))
+}
+
+/// Link to a function on `page`, one of the `page_names`, that works from any page.
+pub fn page_href(page: &str, name: &str) -> String {
+    format!("{}.html#{}", page, slugify(name))
+}
+
+/// File name of every category's page without the extension: the `slugify`d category, made
+/// unique like `anchor_ids` are when it is taken by an earlier category, e.g. `player` next to
+/// `Player`, or by one of the `reserved` pages written besides them.
+pub fn page_names(docs: &Documentation, reserved: &[&str]) -> BTreeMap<String, String> {
+    let mut taken: Vec<String> = reserved.iter().map(|name| name.to_string()).collect();
+    docs.keys()
+        .map(|category| {
+            let name = unique_slug(slugify(category), &taken);
+            taken.push(name.clone());
+            (category.clone(), name)
+        })
+        .collect()
+}
+
+/// `base`, or `base` with the first of `-1`, `-2`, ... appended that none of `taken` has.
+pub fn unique_slug(base: String, taken: &[String]) -> String {
+    let mut slug = base.clone();
+    let mut counter = 0;
+    while taken.contains(&slug) {
+        counter += 1;
+        slug = format!("{}-{}", base, counter);
+    }
+    slug
+}
+
+/// Lowercases `name` and turns every run of other characters than letters and digits into one
+/// `-`, so `Player.kick` becomes `player-kick`. Slugs come out unchanged, links can take either.
+/// Accented Latin letters are folded to ASCII (`Größe` becomes `grosse`), other letters such as
+/// Cyrillic are kept as they are and emoji count as separators.
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::with_capacity(name.len());
+    for c in name.chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if let Some(folded) = ascii_fold(c) {
+            slug.push_str(folded);
+        } else if ('\u{300}'..='\u{36f}').contains(&c) {
+            // combining accents, as in a decomposed `é` or the dot `İ` lowercases to
+        } else if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        // a name like `_` has nothing left to show
+        String::from("item")
+    } else {
+        slug.to_string()
+    }
+}
+
+/// ASCII spelling of a lowercase accented Latin letter.
+fn ascii_fold(c: char) -> Option<&'static str> {
+    let folded = match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'ð' | 'ď' | 'đ' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĵ' => "j",
+        'ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'œ' => "oe",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' | 'ș' => "s",
+        'ß' => "ss",
+        'ţ' | 'ť' | 'ŧ' | 'ț' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    };
+    Some(folded)
+}
+
+/// Element ids of a category's functions: the `slugify`d name, with `-1`, `-2`, ... appended
+/// when two functions end up with the same one, e.g. an overload or `Kick` next to `kick`.
+pub fn anchor_ids(functions: &[Function]) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::with_capacity(functions.len());
+    for func in functions {
+        let id = unique_slug(slugify(&func.name), &ids);
+        ids.push(id);
+    }
+    ids
+}
+
+/// The `anchor_ids` entry of `target`, one of `functions`.
+pub fn anchor_id(functions: &[Function], target: &Function) -> String {
+    functions.iter()
+        .position(|func| std::ptr::eq(func, target))
+        .and_then(|index| anchor_ids(functions).into_iter().nth(index))
+        .unwrap_or_else(|| slugify(&target.name))
+}
+
+/// Heading for the functions without a `--@group` in a category where others have one.
+pub const DEFAULT_GROUP: &str = "Other";
+
+/// A `--@group` title and its functions with their anchor ids.
+pub type FunctionGroup<'a> = (Option<&'a str>, Vec<(&'a Function, String)>);
+
+/// A category's functions paired with their `anchor_ids`, split by `--@group` in the order the
+/// groups first appear. Without any group this is one untitled group, otherwise the ungrouped
+/// functions come last under `DEFAULT_GROUP`.
+pub fn group_functions(functions: &[Function]) -> Vec<FunctionGroup<'_>> {
+    let mut groups: Vec<FunctionGroup> = Vec::new();
+    let mut ungrouped = Vec::new();
+    for (func, id) in functions.iter().zip(anchor_ids(functions)) {
+        let Some(name) = func.group.as_deref() else {
+            ungrouped.push((func, id));
+            continue;
+        };
+        match groups.iter_mut().find(|(group, _)| *group == Some(name)) {
+            Some((_, members)) => members.push((func, id)),
+            None => groups.push((Some(name), vec![(func, id)])),
+        }
+    }
+    
+    if groups.is_empty() {
+        return vec![(None, ungrouped)];
+    }
+    if !ungrouped.is_empty() {
+        groups.push((Some(DEFAULT_GROUP), ungrouped));
+    }
+    groups
+}
+
+/// Id of a function on the single page, prefixed with the category since names repeat across categories.
+pub fn single_page_anchor(category: &str, name: &str) -> String {
+    format!("{}-{}", slugify(category), slugify(name))
+}
+
+/// The in-page table of contents of a category page, in the order the page lists things.
+pub fn render_toc(functions: &[Function], enums: &[Enum]) -> String {
+    let mut toc = String::from(r#"
+        <aside class="toc-sidebar">
+            <div class="toc-title">On this page</div>
+            <ul id="page-toc" class="page-toc">"#);
+    
+    for item in enums {
+        toc.push_str(&toc_item(&enum_anchor(&item.name), &item.name));
+    }
+    for (group, members) in group_functions(functions) {
+        if let Some(group) = group {
+            toc.push_str(&format!(r#"
+                <li class="toc-group">{}</li>"#, escape_html(group)));
+        }
+        for (func, id) in members {
+            toc.push_str(&toc_item(&id, &func.name));
+        }
+    }
+    
+    toc.push_str(r#"
+            </ul>
+        </aside>"#);
+    toc
+}
+
+fn toc_item(id: &str, name: &str) -> String {
+    format!(r#"
+                <li class="toc-item">{}</li>"#, section_link(&format!("#{}", id), "toc-link", name))
+}
+
+/// Link of a sidebar or table of contents entry. A link into the current page also carries the
+/// id it leads to, so the script can highlight the entry of the section being read.
+fn section_link(href: &str, class: &str, text: &str) -> String {
+    match href.strip_prefix('#') {
+        Some(id) => format!(r#"<a href="{}" class="{}" data-target="{}">{}</a>"#,
+            escape_html(href), class, escape_html(id), escape_html(text)),
+        None => format!(r#"<a href="{}" class="{}">{}</a>"#, escape_html(href), class, escape_html(text)),
+    }
+}
+
+/// Title and list of a category's sidebar section, `Navigation::render` adds the wrapper.
+fn render_section(category: &str, functions: &[Function], enums: &[Enum], href: impl Fn(&str) -> String) -> String {
+    let mut section = format!(r#"
+                    <summary class="nav-title">{}</summary>
+                    <ul class="nav-list">"#, escape_html(category));
+    
+    for (group, members) in group_functions(functions) {
+        match group {
+            Some(group) => {
+                section.push_str(&format!(r#"
+                        <li class="nav-group">
+                            <span class="nav-group-title">{}</span>
+                            <ul class="nav-sublist">"#, escape_html(group)));
+                render_items(&mut section, &members, &href);
+                section.push_str(r#"
+                            </ul>
+                        </li>"#);
+            }
+            None => render_items(&mut section, &members, &href),
+        }
+    }
+    
+    if !enums.is_empty() {
+        section.push_str(r#"
+                        <li class="nav-group">
+                            <span class="nav-group-title">Enums</span>
+                            <ul class="nav-sublist">"#);
+        for item in enums {
+            section.push_str(&format!(r#"
+                        <li class="nav-item nav-enum">
+                            {}
+                        </li>"#, section_link(&href(&enum_anchor(&item.name)), "nav-link", &item.name)));
+        }
+        section.push_str(r#"
+                            </ul>
+                        </li>"#);
+    }
+    
+    section.push_str(r#"
+                    </ul>"#);
+    section
+}
+
+fn render_items(section: &mut String, members: &[(&Function, String)], href: &impl Fn(&str) -> String) {
+    for (func, id) in members {
+        section.push_str(&format!(r#"
+                        <li class="nav-item">
+                            {}
+                        </li>"#, section_link(&href(id), "nav-link", &func.name)));
+        
+        for alias in &func.aliases {
+            section.push_str(&format!(r#"
+                        <li class="nav-item nav-alias">
+                            {}
+                        </li>"#, section_link(&href(id), "nav-link", Function::alias_name(alias))));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn slugify_folds_accents_to_ascii() {
+        assert_eq!(slugify("Größe"), "grosse");
+        assert_eq!(slugify("Élève.Créer"), "eleve-creer");
+        assert_eq!(slugify("Añadir Jugador"), "anadir-jugador");
+        assert_eq!(slugify("İstanbul"), "istanbul");
+        // a decomposed `é` loses its combining accent
+        assert_eq!(slugify("Cafe\u{301}"), "cafe");
+    }
+    
+    #[test]
+    fn slugify_keeps_letters_without_an_ascii_form() {
+        assert_eq!(slugify("Игрок.Кик"), "игрок-кик");
+        assert_eq!(slugify("玩家"), "玩家");
+    }
+    
+    #[test]
+    fn slugify_treats_emoji_as_separators() {
+        assert_eq!(slugify("🎉 Party 🎉 Time"), "party-time");
+        assert_eq!(slugify("🔥"), "item");
+    }
+    
+    #[test]
+    fn slugify_is_idempotent() {
+        for name in ["Größe", "Игрок.Кик", "🎉 Party", "Œuvre_Ñ", "玩家:Kick"] {
+            let slug = slugify(name);
+            assert_eq!(slugify(&slug), slug);
+        }
+    }
+    
+    #[test]
+    fn page_names_are_unique_slugs() {
+        let docs: Documentation = ["Player", "player", "My Stuff/#1", "Index"].into_iter()
+            .map(|category| (category.to_string(), Vec::new()))
+            .collect();
+        let pages = page_names(&docs, &["index"]);
+        assert_eq!(pages["Player"], "player");
+        assert_eq!(pages["player"], "player-1");
+        assert_eq!(pages["My Stuff/#1"], "my-stuff-1");
+        assert_eq!(pages["Index"], "index-1");
+    }
+}
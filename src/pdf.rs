@@ -0,0 +1,30 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::Command;
+
+/// Turns the single-page `html` into `pdf` with wkhtmltopdf, or whatever takes the same arguments.
+/// The page is written into `out_dir` for the conversion so the logo and other relative paths resolve.
+pub fn generate_pdf(html: &str, out_dir: &Path, pdf: &Path, converter: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let page = out_dir.join(".manual.html");
+    fs::write(&page, html)?;
+    
+    let status = Command::new(converter)
+        // print.css hides the sidebar, the outline keeps the table of contents to categories and functions
+        .args(["--quiet", "--print-media-type", "--enable-local-file-access", "--outline-depth", "2"])
+        .arg("toc")
+        .arg(&page)
+        .arg(pdf)
+        .status();
+    fs::remove_file(&page)?;
+    
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(format!("{} failed to write {} ({})", converter.display(), pdf.display(), status).into()),
+        Err(error) if error.kind() == ErrorKind::NotFound => Err(format!(
+            "PDF converter {} not found, install wkhtmltopdf or pass its location with --pdf-converter",
+            converter.display()
+        ).into()),
+        Err(error) => Err(format!("could not run {}: {}", converter.display(), error).into()),
+    }
+}
@@ -1,107 +1,1023 @@
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use regex::Regex;
 
-#[derive(Debug, Deserialize, Serialize)]
+use crate::cache::{self, ScanCache};
+use crate::log;
+use crate::validate::{Diagnostic, Severity};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Param {
     pub name: String,
     #[serde(rename = "type")]
     pub param_type: String,
     pub description: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub optional: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub variadic: bool,
+    /// Value used when the argument is left out, written after the type as `number=30`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+impl Param {
+    fn new(name: &str, param_type: &str, description: &str) -> Self {
+        Self {
+            name: name.trim().to_string(),
+            param_type: param_type.trim().to_string(),
+            description: description.trim().to_string(),
+            optional: false,
+            variadic: false,
+            default: None,
+        }
+    }
+    
+    /// Name as written in a signature, variadic parameters keep their `...`.
+    pub fn display_name(&self) -> String {
+        if self.variadic && self.name != "..." {
+            format!("{}...", self.name)
+        } else {
+            self.name.clone()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Return {
     #[serde(rename = "type")]
     pub return_type: String,
     pub description: String,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Field {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: String,
+    pub description: String,
+    /// Set by a `--@readonly` or `--@const` right above the field.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub readonly: bool,
+}
+
+/// A table of named constants documented with `--@enum`, such as `TeamColor = { Red = 1 }`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Enum {
+    pub name: String,
+    pub description: String,
+    pub variants: Vec<EnumVariant>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub source_file: String,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub source_line: usize,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EnumVariant {
+    pub name: String,
+    pub value: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Function {
     pub name: String,
     pub description: String,
+    /// Descriptions in other languages from `--@desc:fr`, by locale, picked with `--locale`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub descriptions: BTreeMap<String, String>,
     pub params: Vec<Param>,
     pub returns: Vec<Return>,
+    /// Errors the function raises, the type is empty when only a description was given.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<Return>,
+    /// Other accepted signatures as written, such as `(x: number): string`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overloads: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<String>,
+    /// Version the function first appeared in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    /// Who wrote the function, each as written after `--@author`, such as `Jane Doe <jane@example.com>`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<String>,
+    /// Position in its category, lower comes first and unset comes after every set one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<i32>,
+    /// Subsection of the category the function is listed under, such as `Movement`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Other names the function is exported under, such as `Player.Boot`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Short note on how to call the function, shown above the examples.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub see: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<Field>,
+    /// Argument names of the declaration, when they could be read from it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<Vec<String>>,
+    /// Set when the function was defined with `:` and receives `self` implicitly.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_method: bool,
+    /// Set when the entry documents a table or another value, such as a constant, instead of a function.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub table: bool,
+    /// Set by a `--@readonly` or `--@const` that no `--@field` follows, for constants and tables
+    /// that must not be changed.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub readonly: bool,
+    /// Set by `--@private` or `--@internal`, such functions are only generated with `--include-private`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub private: bool,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub source_file: String,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub source_line: usize,
+}
+
+fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
+impl Function {
+    /// `file:line` the function was documented at, if it came from a scan.
+    pub fn location(&self) -> String {
+        if self.source_file.is_empty() {
+            String::from("unknown location")
+        } else {
+            format!("{}:{}", self.source_file, self.source_line)
+        }
+    }
+    
+    /// Name an alias is listed under, `Boot` for `Player.Boot`.
+    pub fn alias_name(alias: &str) -> &str {
+        alias.rsplit(['.', ':']).next().unwrap_or(alias)
+    }
+    
+    /// The implicit `self` of a colon method typed as `receiver`, unless it is documented already.
+    pub fn self_param(&self, receiver: &str) -> Option<Param> {
+        let documented = self.params.first().is_some_and(|param| param.name == "self");
+        (self.is_method && !documented)
+            .then(|| Param::new("self", receiver, "The object the method is called on"))
+    }
 }
 
 #[derive(Debug)]
 struct DocBlock {
     class_name: Option<String>,
+    enum_name: Option<String>,
+    /// Name given with `--@name`, shown instead of the one read from the declaration.
+    name: Option<String>,
     description: String,
+    descriptions: BTreeMap<String, String>,
     params: Vec<Param>,
     returns: Vec<Return>,
-    start_line: usize,
+    errors: Vec<Return>,
+    overloads: Vec<String>,
+    deprecated: Option<String>,
+    since: Option<String>,
+    authors: Vec<String>,
+    order: Option<i32>,
+    group: Option<String>,
+    private: bool,
+    /// A `--@readonly` waiting for the `--@field` below it, the whole block is read-only when none follows.
+    readonly: bool,
+    aliases: Vec<String>,
+    usage: String,
+    examples: Vec<String>,
+    see: Vec<String>,
+    fields: Vec<Field>,
 }
 
 impl DocBlock {
-    fn new(start_line: usize) -> Self {
+    /// Adds a line to the description, or the one of `locale` for `--@desc:fr`. Paragraphs are
+    /// separated by a blank line.
+    fn push_description(&mut self, locale: Option<&str>, line: &str, new_paragraph: bool) {
+        let description = match locale {
+            Some(locale) => self.descriptions.entry(locale.to_string()).or_default(),
+            None => &mut self.description,
+        };
+        if !line.is_empty() {
+            push_paragraph_line(description, line, new_paragraph);
+        }
+    }
+    
+    /// Whether the block holds anything that would show up in the docs, a lone `---@class` type
+    /// declaration or `---@diagnostic` line does not.
+    fn documents_something(&self) -> bool {
+        !self.description.is_empty() || !self.params.is_empty() || !self.returns.is_empty()
+            || !self.fields.is_empty() || !self.examples.is_empty()
+    }
+    
+    /// Adds an untagged line to the description of the last item of `tag`, such as the return
+    /// value a `--@return` above it started.
+    fn continue_tag(&mut self, tag: ContinuedTag, line: &str) {
+        let description = match tag {
+            ContinuedTag::Param => self.params.last_mut().map(|param| &mut param.description),
+            ContinuedTag::Return => self.returns.last_mut().map(|ret| &mut ret.description),
+            ContinuedTag::Error => self.errors.last_mut().map(|error| &mut error.description),
+            ContinuedTag::Field => self.fields.last_mut().map(|field| &mut field.description),
+        };
+        if let Some(description) = description {
+            push_paragraph_line(description, line, false);
+        }
+    }
+    
+    /// Adds a line to the `--@usage` note, paragraphs are separated like in the description.
+    fn push_usage(&mut self, line: &str, new_paragraph: bool) {
+        if !line.is_empty() {
+            push_paragraph_line(&mut self.usage, line, new_paragraph);
+        }
+    }
+    
+    fn into_function(self, name: String) -> Function {
+        Function {
+            name,
+            description: self.description,
+            descriptions: self.descriptions.into_iter()
+                .filter(|(_, description)| !description.is_empty())
+                .collect(),
+            params: self.params,
+            returns: self.returns,
+            errors: self.errors,
+            overloads: self.overloads,
+            deprecated: self.deprecated,
+            since: self.since,
+            authors: self.authors,
+            order: self.order,
+            group: self.group,
+            aliases: self.aliases,
+            usage: (!self.usage.is_empty()).then_some(self.usage),
+            examples: self.examples.iter()
+                .map(|example| dedent(example))
+                .filter(|example| !example.is_empty())
+                .collect(),
+            see: self.see,
+            fields: self.fields,
+            arguments: None,
+            is_method: false,
+            table: false,
+            readonly: self.readonly,
+            private: self.private,
+            source_file: String::new(),
+            source_line: 0,
+        }
+    }
+    
+    fn new() -> Self {
         Self {
             class_name: None,
+            enum_name: None,
+            name: None,
             description: String::new(),
+            descriptions: BTreeMap::new(),
             params: Vec::new(),
             returns: Vec::new(),
-            start_line,
+            errors: Vec::new(),
+            overloads: Vec::new(),
+            deprecated: None,
+            since: None,
+            authors: Vec::new(),
+            order: None,
+            group: None,
+            private: false,
+            readonly: false,
+            aliases: Vec::new(),
+            usage: String::new(),
+            examples: Vec::new(),
+            see: Vec::new(),
+            fields: Vec::new(),
+        }
+    }
+}
+
+/// Tags whose description can go on over the following, deeper indented lines.
+#[derive(Debug, Clone, Copy)]
+enum ContinuedTag {
+    Param,
+    Return,
+    Error,
+    Field,
+}
+
+fn push_paragraph_line(text: &mut String, line: &str, new_paragraph: bool) {
+    if !text.is_empty() {
+        text.push_str(if new_paragraph { "\n\n" } else { " " });
+    }
+    text.push_str(line);
+}
+
+pub type Documentation = BTreeMap<String, Vec<Function>>;
+
+/// Descriptions of the categories themselves, from `--@class` blocks that no function follows.
+pub type Categories = BTreeMap<String, String>;
+
+/// Enums of every category, kept apart from the functions and like `Categories` only found by scanning.
+pub type Enums = BTreeMap<String, Vec<Enum>>;
+
+/// What a doc block documents. Functions and enums say whether a `--@class` named their category.
+enum DocItem {
+    Function(String, Box<Function>, bool),
+    Category(String, String),
+    Enum(String, Enum, bool),
+    /// A block with a description or tags that nothing it could document follows.
+    Unattached,
+}
+
+/// Looks up a `--@see` made in `category`, a name documented in that category wins so
+/// `--@see Ban` in `Player` finds `Player.Ban`, anything else is read as `resolve_qualified` does.
+pub fn resolve_reference<'a>(docs: &'a Documentation, category: &str, reference: &str) -> Option<(&'a str, &'a Function)> {
+    find_function(docs, category, reference).or_else(|| resolve_qualified(docs, reference))
+}
+
+/// The functions whose `--@see` leads to each function, by the category and name of the function
+/// they lead to, in documentation order.
+pub type BackReferences<'a> = BTreeMap<(&'a str, &'a str), Vec<(&'a str, &'a Function)>>;
+
+/// Collects every `--@see` edge the other way round, for the "Referenced by" sections.
+pub fn back_references(docs: &Documentation) -> BackReferences<'_> {
+    let mut references = BackReferences::new();
+    
+    for (category, functions) in docs {
+        for func in functions {
+            for reference in &func.see {
+                let Some((target_category, target)) = resolve_reference(docs, category, reference) else {
+                    continue;
+                };
+                if std::ptr::eq(target, func) {
+                    continue;
+                }
+                
+                let sources = references.entry((target_category, target.name.as_str())).or_default();
+                if !sources.iter().any(|(_, source)| std::ptr::eq(*source, func)) {
+                    sources.push((category.as_str(), func));
+                }
+            }
+        }
+    }
+    
+    references
+}
+
+/// Looks up a `Category.name` (or `Category:name`) reference, bare names are looked up in `Global`.
+pub fn resolve_qualified<'a>(docs: &'a Documentation, reference: &str) -> Option<(&'a str, &'a Function)> {
+    let (category, name) = reference
+        .rsplit_once(['.', ':'])
+        .unwrap_or(("Global", reference));
+    find_function(docs, category, name)
+}
+
+fn find_function<'a>(docs: &'a Documentation, category: &str, name: &str) -> Option<(&'a str, &'a Function)> {
+    docs.get_key_value(category)
+        .and_then(|(category, functions)| {
+            functions.iter()
+                .find(|func| func.name == name)
+                .map(|func| (category.as_str(), func))
+        })
+}
+
+/// Scans every `.lua` file below each of `roots` into one documentation, a category found in
+/// several roots gets the functions of all of them. Files whose content is unchanged since `cache`
+/// was written are taken from it instead of being parsed again, and `cache` is updated to this scan.
+pub fn scan_directories(
+    roots: &[PathBuf],
+    exclude: &GlobSet,
+    nested_categories: bool,
+    category_from_path: bool,
+    cache: &mut ScanCache
+) -> Result<Scanned, Box<dyn std::error::Error>> {
+    let mut scan = Scan::new(exclude, nested_categories, category_from_path, cache);
+    for root in roots {
+        scan.root = root;
+        // with several roots a relative path alone could belong to any of them
+        if roots.len() > 1 {
+            scan.prefix = format!("{}/", slash_path(root).trim_end_matches('/'));
         }
+        scan_recursive(root, &mut scan)?;
+    }
+    
+    if scan.reused > 0 {
+        log::info!("Reused {} unchanged files", scan.reused);
     }
+    
+    Ok(scan.finish())
+}
+
+/// Documents a single buffer as if it were the only file scanned, for `--stdin`. `path` is where
+/// the buffer is saved, it names the module and is given as the source of every function.
+pub fn scan_content(content: &str, path: &Path, nested_categories: bool, category_from_path: bool) -> Scanned {
+    let exclude = GlobSet::empty();
+    let mut cache = ScanCache::default();
+    let mut scan = Scan::new(&exclude, nested_categories, category_from_path, &mut cache);
+    
+    let source_file = slash_path(path);
+    let folder_category = category_from_path.then(|| folder_category(&source_file)).flatten();
+    scan.add(parse_lua_content(content, path, &source_file), &source_file, folder_category);
+    
+    scan.finish()
 }
 
-pub type Documentation = HashMap<String, Vec<Function>>;
-pub fn scan_directory(path: &str) -> Result<Documentation, Box<dyn std::error::Error>> {
-    let mut docs: Documentation = HashMap::new();
-    scan_recursive(&Path::new(path), &mut docs)?;
-    Ok(docs)
+/// Everything a scan found.
+#[derive(Debug, Clone, Default)]
+pub struct Scanned {
+    pub docs: Documentation,
+    /// Descriptions of the categories themselves, from `--@class` blocks.
+    pub categories: Categories,
+    pub enums: Enums,
+    /// Problems found while parsing the files, such as unknown tags, for `diagnostics.json`.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Version of what `parse_lua_content` makes of a file, part of the key of `.cache.json`. Bump it
+/// with every change to parsing or to `ParsedFile`, or files cached by an older build keep their
+/// old parse until they are edited.
+pub const PARSER_VERSION: u32 = 4;
+
+/// Everything one file documents, in the order it was found.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ParsedFile {
+    functions: Vec<(String, Function)>,
+    /// Category, description and the line it was found at.
+    categories: Vec<(String, String, usize)>,
+    #[serde(default)]
+    enums: Vec<(String, Enum)>,
+    /// Categories a `--@class` in the file names, `--category-from-path` leaves them as they are.
+    #[serde(default)]
+    classes: BTreeSet<String>,
+    /// Problems found while parsing, by line, reported again whenever the file comes from the cache.
+    #[serde(default)]
+    warnings: Vec<(usize, Warning)>,
+}
+
+/// A problem found while parsing a file.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+enum Warning {
+    Message(String),
+    /// A tag that is neither supported nor ignored on purpose, reported once per build.
+    UnknownTag(String),
+}
+
+struct Scan<'a> {
+    root: &'a Path,
+    /// Put in front of every source path, set when scanning several roots.
+    prefix: String,
+    exclude: &'a GlobSet,
+    /// Lists `Net.Http.Get` as `Get` in `Net.Http` instead of `Http.Get` in `Net`.
+    nested_categories: bool,
+    /// Takes the category from the folder of each file, unless a `--@class` names it.
+    category_from_path: bool,
+    docs: Documentation,
+    categories: Categories,
+    enums: Enums,
+    previous: ScanCache,
+    cache: &'a mut ScanCache,
+    reused: usize,
+    diagnostics: Vec<Diagnostic>,
+    /// Unknown tags reported so far, each is only reported at the first place it is used.
+    unknown_tags: BTreeSet<String>,
+}
+
+impl<'a> Scan<'a> {
+    fn new(exclude: &'a GlobSet, nested_categories: bool, category_from_path: bool, cache: &'a mut ScanCache) -> Self {
+        Scan {
+            root: Path::new(""),
+            prefix: String::new(),
+            exclude,
+            nested_categories,
+            category_from_path,
+            docs: BTreeMap::new(),
+            categories: BTreeMap::new(),
+            enums: BTreeMap::new(),
+            previous: std::mem::take(cache),
+            cache,
+            reused: 0,
+            diagnostics: Vec::new(),
+            unknown_tags: BTreeSet::new(),
+        }
+    }
+    
+    /// Adds what `source_file` documents, `folder_category` is the category `--category-from-path`
+    /// gives its functions and enums.
+    fn add(&mut self, parsed: ParsedFile, source_file: &str, folder_category: Option<String>) {
+        let ParsedFile { functions, categories, enums, classes, warnings } = parsed;
+        for (line, warning) in warnings {
+            match warning {
+                Warning::Message(message) => self.warn(source_file, line, message),
+                Warning::UnknownTag(tag) => {
+                    if self.unknown_tags.insert(tag.clone()) {
+                        self.warn(source_file, line, unknown_tag_message(&tag, source_file, line));
+                    }
+                }
+            }
+        }
+        
+        let from_path = |category: String, name: &mut String| match &folder_category {
+            Some(folder_category) if !classes.contains(&category) => {
+                // the namespace the name was written with stays in front of it, unless it is the folder's
+                if category != "Global" && category != *folder_category {
+                    *name = format!("{}.{}", category, name);
+                }
+                folder_category.clone()
+            }
+            _ => category,
+        };
+        
+        for (category, mut function) in functions {
+            let category = from_path(category, &mut function.name);
+            let category = if self.nested_categories {
+                let (category, name) = nest_category(category, &function.name);
+                function.name = name;
+                category
+            } else {
+                category
+            };
+            self.docs.entry(category).or_default().push(function);
+        }
+        
+        for (category, description, line) in categories {
+            if self.categories.contains_key(&category) {
+                let message = format!(
+                    "Category {} is described more than once, using the description at {}:{}",
+                    category, source_file, line
+                );
+                self.warn(source_file, line, message);
+            }
+            self.categories.insert(category, description);
+        }
+        
+        for (category, mut item) in enums {
+            let category = from_path(category, &mut item.name);
+            let category = if self.nested_categories {
+                let (category, name) = nest_category(category, &item.name);
+                item.name = name;
+                category
+            } else {
+                category
+            };
+            self.enums.entry(category).or_default().push(item);
+        }
+    }
+    
+    /// Logs a warning about `file` and keeps it for `diagnostics.json`.
+    fn warn(&mut self, file: &str, line: usize, message: String) {
+        log::warning!("{}", message);
+        self.diagnostics.push(Diagnostic::at(file, line, Severity::Warning, message));
+    }
+    
+    /// Everything scanned, with the functions and enums of each category in the order they are listed in.
+    fn finish(mut self) -> Scanned {
+        let mut docs = self.docs;
+        for (category, enums) in self.enums.iter_mut() {
+            enums.sort_by(|a, b| a.name.cmp(&b.name));
+            // a category with only enums still gets its own page
+            docs.entry(category.clone()).or_default();
+        }
+        for functions in docs.values_mut() {
+            functions.sort_by(|a, b| {
+                a.order.unwrap_or(i32::MAX).cmp(&b.order.unwrap_or(i32::MAX))
+                    .then_with(|| a.name.cmp(&b.name))
+            });
+        }
+        
+        // whether a word is a class is only known once every file is scanned, so returns listing
+        // one after a comma are split here
+        let classes: BTreeSet<String> = docs.keys().chain(self.categories.keys()).cloned().collect();
+        for func in docs.values_mut().flatten() {
+            func.returns = std::mem::take(&mut func.returns).into_iter()
+                .flat_map(|ret| split_class_returns(ret, &classes))
+                .collect();
+        }
+        
+        Scanned { docs, categories: self.categories, enums: self.enums, diagnostics: self.diagnostics }
+    }
 }
 
-fn scan_recursive(dir: &Path, docs: &mut Documentation) -> Result<(), Box<dyn std::error::Error>> {
+fn scan_recursive(dir: &Path, scan: &mut Scan) -> Result<(), Box<dyn std::error::Error>> {
     if dir.is_dir() {
-        for entry in fs::read_dir(dir)? {
-            let entry = entry?;
-            let path = entry.path();
+        let mut paths = fs::read_dir(dir)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        paths.sort();
+        
+        for path in paths {
+            let relative = slash_path(path.strip_prefix(scan.root).unwrap_or(&path));
+            if scan.exclude.is_match(&relative) {
+                log::detail!("Skipping excluded {}", relative);
+                continue;
+            }
+            
             if path.is_dir() {
-                scan_recursive(&path, docs)?;
+                scan_recursive(&path, scan)?;
             } else if path.extension().and_then(|s| s.to_str()) == Some("lua") {
-                parse_lua_file(&path, docs)?;
+                let content = fs::read_to_string(&path)?;
+                let hash = cache::hash_content(&content);
+                
+                // paths are kept relative to the scanned folder with forward slashes, like a repository path
+                let source_file = format!("{}{}", scan.prefix, relative);
+                
+                let parsed = match scan.previous.get(&source_file, hash) {
+                    Some(parsed) => {
+                        scan.reused += 1;
+                        parsed.clone()
+                    }
+                    None => parse_lua_content(&content, &path, &source_file),
+                };
+                
+                let folder_category = scan.category_from_path.then(|| folder_category(&relative)).flatten();
+                scan.add(parsed.clone(), &source_file, folder_category);
+                scan.cache.insert(source_file, hash, parsed);
             }
         }
     }
     Ok(())
 }
 
-fn parse_lua_file(path: &PathBuf, docs: &mut Documentation) -> Result<(), Box<dyn std::error::Error>> {
-    println!("[ INFO ] Scanning file: {:?}", path);
-    let content = fs::read_to_string(path)?;
-    let lines: Vec<&str> = content.lines().collect();
+/// Category of the files in the folder of `relative` for `--category-from-path`, the folder name
+/// starting with a capital: `lua/player/kick.lua` is in `Player`. `None` for a file directly in the scanned folder.
+fn folder_category(relative: &str) -> Option<String> {
+    let (folder, _) = relative.rsplit_once('/')?;
+    capitalize(folder.rsplit('/').next().unwrap_or(folder))
+}
+
+/// `name` starting with a capital, `None` when it is empty.
+fn capitalize(name: &str) -> Option<String> {
+    let mut chars = name.chars();
+    chars.next().map(|first| first.to_uppercase().chain(chars).collect())
+}
+
+/// Folders that never hold documentation, skipped on top of any `--exclude`.
+const DEFAULT_EXCLUDES: &[&str] = &["**/.git", "**/node_modules"];
+
+/// Compiles the `--exclude` globs, which match paths relative to the scanned folder like `vendor/json.lua`.
+pub fn exclude_set(patterns: &[String]) -> Result<GlobSet, Box<dyn std::error::Error>> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in DEFAULT_EXCLUDES.iter().copied().chain(patterns.iter().map(String::as_str)) {
+        let glob = Glob::new(pattern)
+            .map_err(|error| format!("Invalid --exclude {}: {}", pattern, error))?;
+        builder.add(glob);
+    }
+    Ok(builder.build()?)
+}
+
+fn slash_path(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Parses the content of one lua file. `path` is where it is saved, for the name of its module,
+/// and `source_file` the path it is reported under.
+fn parse_lua_content(content: &str, path: &Path, source_file: &str) -> ParsedFile {
+    log::detail!("Scanning file: {:?}", path);
+    let content = normalize_line_endings(content);
+    let (line_numbers, expanded): (Vec<usize>, Vec<String>) = hoist_trailing_docs(expand_block_comments(&content))
+        .into_iter()
+        .unzip();
+    let lines: Vec<&str> = expanded.iter().map(String::as_str).collect();
+    let modules = module_names(&lines, path);
+    let mut parsed = ParsedFile::default();
     
     let mut i = 0;
     while i < lines.len() {
-        if lines[i].trim_start().starts_with("--@") || lines[i].trim_start().starts_with("-- @") {
-            if let Some(func) = parse_function_doc(&lines, &mut i) {
-                let category = func.0;
-                let function = func.1;
-                println!("[ INFO ] Found function: {} in category {}", function.name, category);
-                docs.entry(category).or_insert_with(Vec::new).push(function);
+        let start = i;
+        if !starts_doc_block(&lines, i) {
+            i += 1;
+            continue;
+        }
+        
+        // parsing moves past the block, so the next one is not skipped
+        let mut warnings = Vec::new();
+        let item = parse_function_doc(&lines, &mut i, &mut warnings);
+        parsed.warnings.extend(warnings.into_iter().map(|(index, warning)| (line_numbers[index] + 1, warning)));
+        match item {
+            Some(DocItem::Function(category, mut function, class)) => {
+                let category = exported_category(category, class, &modules);
+                function.source_file = source_file.to_string();
+                function.source_line = line_numbers[start] + 1;
+                let visibility = if function.private { "private " } else { "" };
+                let kind = if function.table { "value" } else { "function" };
+                log::detail!("Found {}{}: {} in category {}", visibility, kind, function.name, category);
+                if class {
+                    parsed.classes.insert(category.clone());
+                }
+                parsed.functions.push((category, *function));
+            }
+            Some(DocItem::Enum(category, mut item, class)) => {
+                let category = exported_category(category, class, &modules);
+                item.source_file = source_file.to_string();
+                item.source_line = line_numbers[start] + 1;
+                log::detail!("Found enum: {} in category {}", item.name, category);
+                if class {
+                    parsed.classes.insert(category.clone());
+                }
+                parsed.enums.push((category, item));
+            }
+            Some(DocItem::Category(category, description)) => {
+                log::detail!("Found description of category {}", category);
+                parsed.classes.insert(category.clone());
+                parsed.categories.push((category, description, line_numbers[start] + 1));
+            }
+            Some(DocItem::Unattached) => {
+                let line = line_numbers[start] + 1;
+                let message = format!(
+                    "Skipping the doc block at {}:{}, no function or assignment follows it",
+                    source_file, line
+                );
+                parsed.warnings.push((line, Warning::Message(message)));
             }
+            None => {}
+        }
+        if i == start {
+            i += 1;
         }
-        i += 1;
     }
     
-    Ok(())
+    parsed
+}
+
+/// The content without a leading UTF-8 BOM and with `\r\n` and lone `\r` line endings turned
+/// into `\n`, so files saved on Windows parse like any other.
+fn normalize_line_endings(content: &str) -> String {
+    content.strip_prefix('\u{feff}')
+        .unwrap_or(content)
+        .replace("\r\n", "\n")
+        .replace('\r', "\n")
+}
+
+/// Names the local module tables of a file are exported under, such as `M` for `local M = {}`.
+/// `Inventory = M` exports it as `Inventory`, a `return M` outside any function as the file name
+/// (`inventory.lua`, or `inventory/init.lua`), which only replaces a placeholder name like `M`
+/// or `mod` and not one such as `Inventory` that already says what the module is.
+fn module_names(lines: &[&str], path: &Path) -> BTreeMap<String, String> {
+    let local_regex = Regex::new(r"^local\s+([a-zA-Z_][a-zA-Z0-9_]*)\s*=\s*\{").unwrap();
+    let export_regex = Regex::new(r"^(?:_G\.)?([a-zA-Z_][a-zA-Z0-9_]*(?:\.[a-zA-Z_][a-zA-Z0-9_]*)*)\s*=\s*([a-zA-Z_][a-zA-Z0-9_]*)\s*;?\s*$").unwrap();
+    let return_regex = Regex::new(r"^return\s+([a-zA-Z_][a-zA-Z0-9_]*)\s*;?\s*$").unwrap();
+    
+    let locals: BTreeSet<&str> = lines.iter()
+        .filter_map(|line| local_regex.captures(line.trim()))
+        .filter_map(|captures| captures.get(1).map(|m| m.as_str()))
+        .collect();
+    if locals.is_empty() {
+        return BTreeMap::new();
+    }
+    
+    let mut modules = BTreeMap::new();
+    for line in lines {
+        // only a return that is not indented leaves the file, one inside a function does not
+        if let Some(captures) = return_regex.captures(line)
+            && locals.contains(&captures[1])
+            && is_placeholder_module(&captures[1])
+            && let Some(name) = file_module_name(path)
+        {
+            modules.entry(captures[1].to_string()).or_insert(name);
+        }
+        if let Some(captures) = export_regex.captures(line.trim())
+            && locals.contains(&captures[2])
+            && captures[1] != captures[2]
+        {
+            modules.insert(captures[2].to_string(), captures[1].to_string());
+        }
+    }
+    modules
+}
+
+/// A module table name that says nothing about the module: a single letter like `M` or `_M`,
+/// or a lowercase word like `mod` or `lib`.
+fn is_placeholder_module(name: &str) -> bool {
+    name.trim_start_matches('_').chars().count() <= 1 || !name.chars().any(char::is_uppercase)
+}
+
+/// The module name of a file for `return M`, capitalized like a category. Garry's mod realm
+/// prefixes are left out, `sh_inventory.lua` is `Inventory`.
+fn file_module_name(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_str()?;
+    let name = if stem == "init" {
+        path.parent()?.file_name()?.to_str()?
+    } else {
+        ["sh_", "sv_", "cl_"].iter().find_map(|prefix| stem.strip_prefix(prefix)).unwrap_or(stem)
+    };
+    capitalize(name)
+}
+
+/// The category of a function or enum found in a local module table, the name the table is
+/// exported under. A `--@class` is kept as it is.
+fn exported_category(category: String, class: bool, modules: &BTreeMap<String, String>) -> String {
+    match modules.get(&category) {
+        Some(name) if !class => name.clone(),
+        _ => category,
+    }
+}
+
+/// Rewrites `--[[ ... ]]` (and `--[==[ ... ]==]`) comments that start a line into `--` line
+/// comments, one per line or per tag, so block doc comments go through the same tag parsing.
+/// Every line is paired with the zero-based line it came from.
+fn expand_block_comments(content: &str) -> Vec<(usize, String)> {
+    let open_regex = Regex::new(r"^(\s*)--\[(=*)\[").unwrap();
+    let inline_tag_regex = Regex::new(r"\s+@[a-zA-Z]").unwrap();
+    let mut lines = Vec::new();
+    let mut closing: Option<String> = None;
+    
+    for (line_number, line) in content.lines().enumerate() {
+        let (indent, body) = match &closing {
+            Some(_) => ("", line),
+            None => match open_regex.captures(line) {
+                Some(captures) => {
+                    closing = Some(format!("]{}]", &captures[2]));
+                    (captures.get(1).map_or("", |m| m.as_str()), &line[captures[0].len()..])
+                }
+                None => {
+                    lines.push((line_number, line.to_string()));
+                    continue;
+                }
+            },
+        };
+        
+        let body = match closing.as_deref().and_then(|close| body.find(close)) {
+            Some(pos) => {
+                closing = None;
+                &body[..pos]
+            }
+            None => body,
+        };
+        
+        // tags become `--@tag`, anything else keeps its indentation for examples
+        if body.trim_start().starts_with('@') {
+            let body = body.trim();
+            let mut starts: Vec<usize> = inline_tag_regex.find_iter(body).map(|m| m.end() - 2).collect();
+            starts.insert(0, 0);
+            starts.push(body.len());
+            for range in starts.windows(2) {
+                lines.push((line_number, format!("{}--{}", indent, body[range[0]..range[1]].trim())));
+            }
+        } else {
+            lines.push((line_number, format!("{}--{}", indent, body.trim_end())));
+        }
+    }
+    
+    lines
+}
+
+/// Moves doc tags written after a declaration, as in `function Foo() -- @desc text` or on the
+/// lines indented into its body, above it. Placed right under a doc block the declaration
+/// already has, both are parsed as one block instead of documenting the function twice.
+fn hoist_trailing_docs(lines: Vec<(usize, String)>) -> Vec<(usize, String)> {
+    let mut hoisted_lines: Vec<(usize, String)> = Vec::with_capacity(lines.len());
+    let mut i = 0;
+    
+    while i < lines.len() {
+        let (line_number, line) = &lines[i];
+        if is_doc_comment(line) || extract_function_name(line).is_none() {
+            hoisted_lines.push(lines[i].clone());
+            i += 1;
+            continue;
+        }
+        
+        let mut tags = Vec::new();
+        let mut declaration = line.clone();
+        if let Some(start) = comment_start(line)
+            && is_tag_line(&line[start..])
+        {
+            tags.push((*line_number, line[start..].trim_end().to_string()));
+            declaration = line[..start].trim_end().to_string();
+        }
+        
+        // tag lines inside the body belong to it, unless they lead into a nested declaration
+        let indent = line.len() - line.trim_start().len();
+        let mut end = i + 1;
+        while end < lines.len()
+            && is_tag_line(&lines[end].1)
+            && lines[end].1.len() - lines[end].1.trim_start().len() > indent
+        {
+            end += 1;
+        }
+        if lines.get(end).is_some_and(|(_, next)| extract_function_name(next).is_some()) {
+            end = i + 1;
+        }
+        tags.extend(lines[i + 1..end].iter().map(|(number, tag)| (*number, tag.trim().to_string())));
+        
+        let mut insert_at = hoisted_lines.len();
+        while insert_at > 0 && hoisted_lines[insert_at - 1].1.trim().is_empty() {
+            insert_at -= 1;
+        }
+        if insert_at == 0 || !is_doc_comment(&hoisted_lines[insert_at - 1].1) {
+            insert_at = hoisted_lines.len();
+        }
+        hoisted_lines.splice(insert_at..insert_at, tags);
+        hoisted_lines.push((*line_number, declaration));
+        i = end;
+    }
+    
+    hoisted_lines
+}
+
+/// Byte offset of the `--` starting a trailing comment, dashes inside strings do not count.
+fn comment_start(line: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut chars = line.char_indices().peekable();
+    
+    while let Some((position, c)) = chars.next() {
+        match quote {
+            Some(_) if c == '\\' => {
+                chars.next();
+            }
+            Some(open) if c == open => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '-' && chars.peek().is_some_and(|(_, next)| *next == '-') => return Some(position),
+            None => {}
+        }
+    }
+    
+    None
+}
+
+/// A doc block starts at a tag, or at LuaLS `--- description` lines that lead into tags.
+fn starts_doc_block(lines: &[&str], index: usize) -> bool {
+    is_tag_line(lines[index])
+        || (is_luals_description(lines[index])
+            && lines[index..].iter()
+                .find(|line| !is_luals_description(line) && !is_separator(line))
+                .is_some_and(|line| is_tag_line(line)))
 }
 
 fn is_doc_comment(line: &str) -> bool {
     let trimmed = line.trim_start();
-    trimmed.starts_with("--@") || trimmed.starts_with("-- @") || 
+    is_tag_line(line) || is_luals_description(line) || is_separator(line) ||
     (trimmed.starts_with("--") && !trimmed.starts_with("---") && !trimmed.starts_with("-- TODO") && !trimmed.starts_with("-- FIXME"))
 }
 
-fn extract_doc_content(line: &str) -> String {
+fn is_tag_line(line: &str) -> bool {
+    strip_comment_dashes(line).is_some_and(|content| content.trim_start().starts_with('@'))
+}
+
+/// A `--- text` line, but not a `---` or `-------` separator.
+fn is_luals_description(line: &str) -> bool {
     line.trim_start()
-        .trim_start_matches("--@")
-        .trim_start_matches("-- @")
-        .trim_start_matches("--")
+        .strip_prefix("---")
+        .is_some_and(|rest| !rest.trim().is_empty() && !rest.trim().starts_with('-') && !rest.trim_start().starts_with('@'))
+}
+
+/// A line made only of dashes, used to separate comment sections.
+fn is_separator(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.starts_with("---") && trimmed.chars().all(|c| c == '-')
+}
+
+/// Removes the `--` or LuaLS `---` that starts a comment line.
+fn strip_comment_dashes(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    trimmed.strip_prefix("---").or_else(|| trimmed.strip_prefix("--"))
+}
+
+/// Width of the whitespace between the comment dashes and the text of a line.
+fn comment_indent(line: &str) -> usize {
+    let content = strip_comment_dashes(line).unwrap_or(line);
+    content.len() - content.trim_start().len()
+}
+
+/// Strips the comment dashes and a single separating space, keeping any further indentation.
+fn extract_example_line(line: &str) -> &str {
+    let content = strip_comment_dashes(line).unwrap_or(line).trim_end();
+    content.strip_prefix(' ').unwrap_or(content)
+}
+
+/// Removes the indentation shared by all non-blank lines and any surrounding blank lines.
+/// Only spaces and tabs count as indentation, so the cut never lands inside a multi-byte character.
+fn dedent(text: &str) -> String {
+    let indent = text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start_matches([' ', '\t']).len())
+        .min()
+        .unwrap_or(0);
+    
+    text.lines()
+        .map(|line| line.get(indent..).unwrap_or("").trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_matches('\n')
+        .to_string()
+}
+
+fn extract_doc_content(line: &str) -> String {
+    if is_separator(line) {
+        return String::new();
+    }
+    
+    let content = strip_comment_dashes(line).unwrap_or(line).trim_start();
+    content.strip_prefix('@')
+        .unwrap_or(content)
         .trim()
         .to_string()
 }
@@ -110,7 +1026,7 @@ fn categorize_function(func_name: &str, class_name: &Option<String>) -> (String,
     // if we have a @class annotation, use that as the category
     if let Some(class) = class_name {
         let function_name = if func_name.contains('.') {
-            func_name.split('.').last().unwrap_or(func_name)
+            func_name.split('.').next_back().unwrap_or(func_name)
         } else {
             func_name
         };
@@ -126,34 +1042,152 @@ fn categorize_function(func_name: &str, class_name: &Option<String>) -> (String,
     }
 }
 
-fn parse_function_doc(lines: &[&str], index: &mut usize) -> Option<(String, Function)> {
-    let mut doc_block = DocBlock::new(*index);
+/// Moves the namespace left in a name by `categorize_function` into the category, for
+/// `--nested-categories`. Files are parsed the flat way so their cached parse fits either.
+fn nest_category(category: String, name: &str) -> (String, String) {
+    match name.rsplit_once('.') {
+        Some((namespace, name)) => (format!("{}.{}", category, namespace), name.to_string()),
+        None => (category, name.to_string()),
+    }
+}
+
+/// Parses the doc block starting at `index`, adding problems with it to `warnings` by the index of their line.
+fn parse_function_doc(lines: &[&str], index: &mut usize, warnings: &mut Vec<(usize, Warning)>) -> Option<DocItem> {
+    let mut doc_block = DocBlock::new();
+    let mut in_example = false;
+    // untagged lines keep adding to the description (or the usage note) until the next tag,
+    // a blank comment line in between starts a new paragraph
+    let mut in_description = false;
+    // set while the description being written is the one of a `--@desc:fr`
+    let mut description_locale: Option<String> = None;
+    let mut in_usage = false;
+    let mut paragraph_break = false;
+    // the tag lines indented deeper than it continue, with the indentation of its own line
+    let mut continued: Option<(ContinuedTag, usize)> = None;
 
     while *index < lines.len() && is_doc_comment(lines[*index]) {
         let content = extract_doc_content(lines[*index]);
         
+        if in_example && !is_tag_line(lines[*index]) {
+            if let Some(example) = doc_block.examples.last_mut() {
+                if !example.is_empty() {
+                    example.push('\n');
+                }
+                example.push_str(extract_example_line(lines[*index]));
+            }
+            *index += 1;
+            continue;
+        }
+        in_example = false;
+        
+        if !is_tag_line(lines[*index]) {
+            if content.is_empty() {
+                paragraph_break = in_description || in_usage;
+                continued = None;
+            } else if let Some((tag, indent)) = continued
+                && comment_indent(lines[*index]) > indent
+            {
+                doc_block.continue_tag(tag, &content);
+            } else if in_usage {
+                doc_block.push_usage(&content, paragraph_break);
+                paragraph_break = false;
+            } else if in_description || doc_block.description.is_empty() {
+                doc_block.push_description(description_locale.as_deref(), &content, paragraph_break);
+                in_description = true;
+                paragraph_break = false;
+            }
+            *index += 1;
+            continue;
+        }
+        in_description = false;
+        description_locale = None;
+        in_usage = false;
+        continued = None;
+        let content = canonical_tag(content);
+        let indent = comment_indent(lines[*index]);
+        
         if let Some(tag_content) = content.strip_prefix("class ") {
             doc_block.class_name = Some(tag_content.trim().to_string());
+        } else if let Some(tag_content) = content.strip_prefix("enum ") {
+            doc_block.enum_name = Some(tag_content.trim().to_string());
+        } else if let Some(tag_content) = content.strip_prefix("name ") {
+            doc_block.name = Some(tag_content.trim().to_string());
         } else if let Some(tag_content) = content.strip_prefix("desc ") {
-            if doc_block.description.is_empty() {
-                doc_block.description = tag_content.trim().to_string();
-            } else {
-                doc_block.description.push(' ');
-                doc_block.description.push_str(tag_content.trim());
+            doc_block.push_description(None, tag_content.trim(), paragraph_break);
+            in_description = true;
+        } else if let Some(tag_content) = content.strip_prefix("desc:") {
+            // `--@desc:fr Bonjour`, the text may also start on the next line
+            let (locale, text) = tag_content.split_once(char::is_whitespace).unwrap_or((tag_content, ""));
+            if !locale.is_empty() {
+                doc_block.push_description(Some(locale), text.trim(), paragraph_break);
+                description_locale = Some(locale.to_string());
+                in_description = true;
             }
         } else if let Some(tag_content) = content.strip_prefix("param ") {
             if let Some(param) = parse_param(tag_content) {
                 doc_block.params.push(param);
+                continued = Some((ContinuedTag::Param, indent));
+            }
+        } else if let Some(tag_content) = content.strip_prefix("tparam").filter(|rest| rest.starts_with([' ', '['])) {
+            if let Some(param) = parse_tparam(tag_content) {
+                doc_block.params.push(param);
+                continued = Some((ContinuedTag::Param, indent));
             }
-        } else if let Some(tag_content) = content.strip_prefix("return ") {
-            if let Some(ret) = parse_return(tag_content) {
-                doc_block.returns.push(ret);
+        } else if let Some(tag_content) = content.strip_prefix("return ").or_else(|| content.strip_prefix("treturn ")) {
+            doc_block.returns.extend(parse_returns(tag_content, &BTreeSet::new()));
+            continued = Some((ContinuedTag::Return, indent));
+        } else if let Some(tag_content) = content.strip_prefix("throws ").or_else(|| content.strip_prefix("error ")) {
+            doc_block.errors.push(parse_error(tag_content));
+            continued = Some((ContinuedTag::Error, indent));
+        } else if let Some(tag_content) = content.strip_prefix("overload ") {
+            doc_block.overloads.push(tag_content.trim().to_string());
+        } else if content == "deprecated" {
+            doc_block.deprecated = Some(String::new());
+        } else if let Some(tag_content) = content.strip_prefix("deprecated ") {
+            doc_block.deprecated = Some(tag_content.trim().to_string());
+        } else if content == "private" || content == "internal" {
+            doc_block.private = true;
+        } else if content == "readonly" || content == "const" {
+            doc_block.readonly = true;
+        } else if let Some(tag_content) = content.strip_prefix("since ") {
+            doc_block.since = Some(tag_content.trim().to_string());
+        } else if let Some(tag_content) = content.strip_prefix("author ") {
+            doc_block.authors.push(tag_content.trim().to_string());
+        } else if let Some(tag_content) = content.strip_prefix("order ") {
+            match tag_content.trim().parse() {
+                Ok(order) => doc_block.order = Some(order),
+                Err(_) => {
+                    let message = format!("Ignoring @order {}, it is not a whole number", tag_content.trim());
+                    warnings.push((*index, Warning::Message(message)));
+                }
             }
-        } else if content.starts_with('@') {
-            continue;
-        } else if !content.trim().is_empty() && doc_block.description.is_empty() {
-            doc_block.description = content.trim().to_string();
+        } else if let Some(tag_content) = content.strip_prefix("group ") {
+            doc_block.group = Some(tag_content.trim().to_string());
+        } else if let Some(tag_content) = content.strip_prefix("alias ") {
+            doc_block.aliases.push(tag_content.trim().to_string());
+        } else if let Some(tag_content) = content.strip_prefix("field ") {
+            if let Some(param) = split_param(tag_content.trim()) {
+                doc_block.fields.push(Field {
+                    name: param.name,
+                    field_type: param.param_type,
+                    description: param.description,
+                    readonly: std::mem::take(&mut doc_block.readonly),
+                });
+                continued = Some((ContinuedTag::Field, indent));
+            }
+        } else if let Some(tag_content) = content.strip_prefix("see ") {
+            doc_block.see.push(tag_content.trim().to_string());
+        } else if content == "usage" || content.starts_with("usage ") {
+            // a second --@usage starts a new paragraph
+            doc_block.push_usage(content["usage".len()..].trim(), true);
+            in_usage = true;
+        } else if content == "example" || content.starts_with("example ") {
+            doc_block.examples.push(content["example".len()..].trim().to_string());
+            in_example = true;
+        } else if !content.is_empty() && !KNOWN_TAGS.contains(&tag_name(&content)) {
+            warnings.push((*index, Warning::UnknownTag(tag_name(&content).to_string())));
         }
+        paragraph_break = false;
         
         *index += 1;
     }
@@ -161,107 +1195,673 @@ fn parse_function_doc(lines: &[&str], index: &mut usize) -> Option<(String, Func
     while *index < lines.len() && lines[*index].trim().is_empty() {
         *index += 1;
     }
+    
+    // an enum documents its variants with `--@field Name value description`, not the table below
+    if let Some(enum_name) = &doc_block.enum_name {
+        let (category, name) = categorize_function(enum_name, &doc_block.class_name);
+        let variants = doc_block.fields.into_iter()
+            .map(|field| EnumVariant { name: field.name, value: field.field_type, description: field.description })
+            .collect();
+        return Some(DocItem::Enum(category, Enum {
+            name,
+            description: doc_block.description,
+            variants,
+            source_file: String::new(),
+            source_line: 0,
+        }, doc_block.class_name.is_some()));
+    }
+    
+    // `--@name Player:Teleport` replaces the declared name, the colon makes it a method
+    let name_override = doc_block.name.take().map(|name| match name.rsplit_once(':') {
+        Some((table, method)) => (format!("{}.{}", table, method), true),
+        None => (name, false),
+    });
 
     for lookahead in 0..3 {
-        if *index + lookahead >= lines.len() {
+        // the next doc block starts before any function
+        if *index + lookahead >= lines.len() || starts_doc_block(lines, *index + lookahead) {
             break;
         }
         
-        if let Some(func_name) = extract_function_name(lines[*index + lookahead]) {
+        // a block with fields documents the table it is assigned to
+        let line = lines[*index + lookahead];
+        if !doc_block.fields.is_empty()
+            && let Some(table_name) = extract_table_name(line)
+        {
+            let table_name = name_override.clone().map_or(table_name, |(name, _)| name);
+            let (category, name) = categorize_function(&table_name, &doc_block.class_name);
+            let class = doc_block.class_name.is_some();
+            let mut function = doc_block.into_function(name);
+            function.table = true;
+            return Some(DocItem::Function(category, Box::new(function), class));
+        }
+        
+        if let Some(declared) = extract_function_name(&continued_line(lines, *index + lookahead)) {
+            let (func_name, is_method) = name_override.clone().unwrap_or(declared);
             let (category, name) = categorize_function(&func_name, &doc_block.class_name);
+            let class = doc_block.class_name.is_some();
+            let mut function = doc_block.into_function(name);
+            function.is_method = is_method;
             
-            return Some((category, Function {
-                name,
-                description: doc_block.description,
-                params: doc_block.params,
-                returns: doc_block.returns,
-            }));
+            let declaration = declaration_text(lines, *index + lookahead);
+            if let Some((_, arguments)) = extract_function_signature(&declaration) {
+                // list the parameters in the order they are declared in
+                function.params.sort_by_key(|param| declaration_position(param, &arguments));
+                function.arguments = Some(arguments);
+            }
+            return Some(DocItem::Function(category, Box::new(function), class));
         }
     }
     
-    None
+    // a class block above its own table (`Player = Player or {}`) still describes the category,
+    // above any other assignment it documents that value, such as a constant
+    if let Some(value_name) = lines.get(*index).and_then(|line| extract_assignment_name(line))
+        && doc_block.class_name.as_ref() != Some(&value_name)
+    {
+        let value_name = name_override.map_or(value_name, |(name, _)| name);
+        let (category, name) = categorize_function(&value_name, &doc_block.class_name);
+        let class = doc_block.class_name.is_some();
+        let mut function = doc_block.into_function(name);
+        function.table = true;
+        return Some(DocItem::Function(category, Box::new(function), class));
+    }
+    
+    // a named block documents a function even without a declaration below it, such as one
+    // defined in C or built by a wrapper
+    if let Some((func_name, is_method)) = name_override {
+        let (category, name) = categorize_function(&func_name, &doc_block.class_name);
+        let class = doc_block.class_name.is_some();
+        let mut function = doc_block.into_function(name);
+        function.is_method = is_method;
+        return Some(DocItem::Function(category, Box::new(function), class));
+    }
+    
+    // a class block without a function describes the category itself
+    match doc_block.class_name {
+        Some(class_name) if !doc_block.description.is_empty() => {
+            Some(DocItem::Category(class_name, doc_block.description))
+        }
+        _ if doc_block.documents_something() => Some(DocItem::Unattached),
+        _ => None,
+    }
 }
 
-fn parse_return(content: &str) -> Option<Return> {
-    let content = content.trim();
+/// Other spellings of a tag, read as the canonical one they map to.
+const TAG_ALIASES: &[(&str, &str)] = &[
+    ("returns", "return"), ("ret", "return"),
+    ("arg", "param"), ("argument", "param"), ("parameter", "param"),
+    ("description", "desc"), ("raises", "throws"), ("examples", "example"),
+];
+
+/// Tags that are either handled above or LuaLS annotations that are fine to ignore, anything
+/// else is reported once as unknown.
+const KNOWN_TAGS: &[&str] = &[
+    "class", "enum", "name", "desc", "param", "tparam", "return", "treturn", "throws", "error", "overload",
+    "deprecated", "private", "internal", "readonly", "const", "since", "author", "order", "group",
+    "alias", "field", "see", "usage", "example",
+    "meta", "type", "generic", "vararg", "diagnostic", "module", "async", "nodiscard", "cast", "operator",
+];
+
+/// The tag a line's content starts with, without its content or an LDoc `[opt]` modifier.
+fn tag_name(content: &str) -> &str {
+    content.split(|c: char| c.is_whitespace() || c == '[').next().unwrap_or_default()
+}
+
+/// Rewrites an aliased tag like `returns` to its canonical name, keeping the rest of the line
+/// and a locale like the `:fr` of `description:fr`.
+fn canonical_tag(content: String) -> String {
+    let tag = tag_name(&content).split(':').next().unwrap_or_default();
+    match TAG_ALIASES.iter().find(|(alias, _)| *alias == tag) {
+        Some((alias, canonical)) => format!("{}{}", canonical, &content[alias.len()..]),
+        None => content,
+    }
+}
+
+/// The warning about an unknown `tag` used at `file:line`, naming the known tag it is closest to.
+fn unknown_tag_message(tag: &str, file: &str, line: usize) -> String {
+    match closest_tag(tag) {
+        Some(known) => format!("Ignoring unknown tag @{} at {}:{}, did you mean @{}?", tag, file, line, known),
+        None => format!(
+            "Ignoring unknown tag @{} at {}:{}, see the Tags section of the README for the supported ones",
+            tag, file, line
+        ),
+    }
+}
+
+/// The known tag or alias `tag` is most likely a typo of, if any is only a few edits away.
+fn closest_tag(tag: &str) -> Option<&'static str> {
+    let tag = tag.to_lowercase();
+    KNOWN_TAGS.iter()
+        .chain(TAG_ALIASES.iter().map(|(alias, _)| alias))
+        .map(|known| (edit_distance(&tag, known), *known))
+        .filter(|(distance, known)| *distance <= (known.len() / 3).max(1))
+        .min()
+        .map(|(_, known)| known)
+}
+
+/// Levenshtein distance between `a` and `b`, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+const LUA_TYPES: &[&str] = &[
+    "nil", "boolean", "bool", "number", "integer", "string", "table",
+    "function", "userdata", "thread", "any", "void",
+];
+
+/// Parses a return tag, which may list several `type description` pairs separated by commas.
+/// A pair after the first only starts at a word that surely is a type, a Lua type, a union, a
+/// generic or one of `classes`, so `boolean True if found, False otherwise` stays one return.
+fn parse_returns(content: &str, classes: &BTreeSet<String>) -> Vec<Return> {
+    let segments = split_top_level(content.trim(), ',');
+    
+    let is_tuple = segments.len() > 1 && segments.iter().enumerate().all(|(i, segment)| {
+        let (return_type, description) = split_type(segment);
+        let is_type = if i == 0 { looks_like_type(return_type) } else { is_known_type(return_type, classes) };
+        is_type && !description.is_empty()
+    });
     
-    if let Some(comma_pos) = content.find(',') {
-        let return_type = content[..comma_pos].trim().to_string();
-        let description = content[comma_pos + 1..].trim().to_string();
-        return Some(Return { return_type, description });
+    if is_tuple {
+        segments.iter()
+            .map(|segment| {
+                let (return_type, description) = split_type(segment);
+                Return { return_type: return_type.to_string(), description: description.to_string() }
+            })
+            .collect()
+    } else {
+        parse_return(content).into_iter().collect()
+    }
+}
+
+/// `ret` split into several returns when its description goes on with a pair starting at one of
+/// `classes`, such as `Player The target` in `--@return boolean Found, Player The target`.
+fn split_class_returns(ret: Return, classes: &BTreeSet<String>) -> Vec<Return> {
+    if ret.return_type.is_empty() || !ret.description.contains(',') {
+        return vec![ret];
     }
+    let returns = parse_returns(&format!("{} {}", ret.return_type, ret.description), classes);
+    if returns.len() > 1 { returns } else { vec![ret] }
+}
+
+fn parse_return(content: &str) -> Option<Return> {
+    let content = content.trim();
     
-    if let Some(space_pos) = content.find(' ') {
-        let return_type = content[..space_pos].trim().to_string();
-        let description = content[space_pos + 1..].trim().to_string();
-        return Some(Return { return_type, description });
+    // `type, description` only when the part before the comma is a single type
+    if let Some(comma_pos) = find_top_level(content, ',')
+        && let (return_type, description) = split_at_delimiter(content, comma_pos, ',')
+        && find_top_level(return_type.trim(), ' ').is_none()
+    {
+        return Some(Return { return_type: return_type.trim().to_string(), description: description.trim().to_string() });
     }
     
+    let (return_type, description) = split_type(content);
     Some(Return {
-        return_type: content.to_string(),
-        description: String::new(),
+        return_type: return_type.to_string(),
+        description: description.to_string(),
     })
 }
 
-fn parse_param(content: &str) -> Option<Param> {
+/// Parses a `throws` or `error` tag. Unlike a return the type is optional, so the first word only
+/// counts as one when it clearly is a type: `--@throws string Invalid target` or `--@throws Invalid target`.
+fn parse_error(content: &str) -> Return {
+    let (error_type, description) = split_type(content);
+    let base = error_type.trim_end_matches('?');
+    if LUA_TYPES.contains(&base) || error_type.contains(['|', '<']) || base.ends_with("Error") {
+        Return { return_type: error_type.to_string(), description: description.to_string() }
+    } else {
+        Return { return_type: String::new(), description: content.trim().to_string() }
+    }
+}
+
+/// Splits `type description`, keeping generic types like `table<string, number>` whole.
+fn split_type(content: &str) -> (&str, &str) {
     let content = content.trim();
-    
-    if let Some(colon_pos) = content.find(':') {
-        let name = content[..colon_pos].trim().to_string();
-        let rest = &content[colon_pos + 1..];
-        
-        if let Some(space_pos) = rest.find(' ') {
-            let param_type = rest[..space_pos].trim().to_string();
-            let description = rest[space_pos + 1..].trim().to_string();
-            return Some(Param { name, param_type, description });
-        } else {
-            let param_type = rest.trim().to_string();
-            return Some(Param { name, param_type, description: String::new() });
+    match find_top_level(content, ' ') {
+        Some(space_pos) => {
+            let (first, rest) = split_at_delimiter(content, space_pos, ' ');
+            (first, rest.trim())
+        }
+        None => (content, ""),
+    }
+}
+
+fn looks_like_type(word: &str) -> bool {
+    let base = word.trim_end_matches('?').trim_end_matches("[]");
+    is_known_type(word, &BTreeSet::new()) || base.starts_with(|c: char| c.is_ascii_uppercase())
+}
+
+/// Whether `word` is a type beyond doubt, unlike a capitalized word that may start a sentence.
+fn is_known_type(word: &str, classes: &BTreeSet<String>) -> bool {
+    let base = word.trim_end_matches('?').trim_end_matches("[]");
+    LUA_TYPES.contains(&base) || word.contains(['|', '<']) || classes.contains(base)
+}
+
+/// Byte position of the first `delimiter` that is not nested inside `<>`, `()`, `{}` or `[]`.
+fn find_top_level(content: &str, delimiter: char) -> Option<usize> {
+    let mut depth = 0usize;
+    for (pos, c) in content.char_indices() {
+        match c {
+            '<' | '(' | '{' | '[' => depth += 1,
+            '>' | ')' | '}' | ']' => depth = depth.saturating_sub(1),
+            _ if c == delimiter && depth == 0 => return Some(pos),
+            _ => {}
         }
     }
+    None
+}
+
+/// The text before and after the `delimiter` found at byte `pos`. The whole delimiter is skipped
+/// rather than one byte, so the cut stays on a character boundary whatever surrounds it.
+fn split_at_delimiter(content: &str, pos: usize, delimiter: char) -> (&str, &str) {
+    let (before, rest) = content.split_at(pos);
+    (before, &rest[delimiter.len_utf8()..])
+}
+
+/// Splits on every top-level `delimiter`, trimming each part.
+pub fn split_top_level(content: &str, delimiter: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut rest = content;
+    while let Some(pos) = find_top_level(rest, delimiter) {
+        let (part, after) = split_at_delimiter(rest, pos, delimiter);
+        parts.push(part.trim());
+        rest = after;
+    }
+    parts.push(rest.trim());
+    parts
+}
+
+fn parse_param(content: &str) -> Option<Param> {
+    split_param(content.trim()).map(with_modifiers).map(with_default)
+}
+
+/// Parses an LDoc `tparam` tag, which puts the type first: `--@tparam string name description`.
+/// `--@tparam[opt] string name` marks the parameter optional, like `name?` does.
+fn parse_tparam(content: &str) -> Option<Param> {
+    let (optional, content) = match content.strip_prefix("[opt") {
+        Some(rest) => (true, &rest[rest.find(']')? + 1..]),
+        None => (false, content.strip_prefix(' ')?),
+    };
+    
+    let (param_type, rest) = split_type(content);
+    let (name, description) = split_type(rest);
+    if name.is_empty() {
+        return None;
+    }
+    
+    let mut param = with_default(with_modifiers(Param::new(name, param_type, description)));
+    param.optional |= optional;
+    Some(param)
+}
+
+/// Applies the `?` and `...` a parameter name can end with.
+fn with_modifiers(mut param: Param) -> Param {
+    if let Some(name) = param.name.strip_suffix('?') {
+        param.name = name.trim_end().to_string();
+        param.optional = true;
+    }
     
-    let parts: Vec<&str> = content.splitn(3, ',').map(|s| s.trim()).collect();
-    if parts.len() >= 2 {
-        return Some(Param {
-            name: parts[0].to_string(),
-            param_type: parts[1].to_string(),
-            description: parts.get(2).unwrap_or(&"").to_string(),
-        });
+    // both a bare `...` and a named `args...` take the rest of the arguments
+    if param.name == "..." {
+        param.variadic = true;
+    } else if let Some(name) = param.name.strip_suffix("...") {
+        param.name = name.trim_end().to_string();
+        param.variadic = true;
     }
     
-    let words: Vec<&str> = content.split_whitespace().collect();
-    if words.len() >= 2 {
-        return Some(Param {
-            name: words[0].to_string(),
-            param_type: words[1].to_string(),
-            description: words[2..].join(" "),
-        });
+    param
+}
+
+/// Splits a default value off the type, `number=30` or `string="hello world"`. A quoted default
+/// can hold spaces, the part of it after the first one is taken back from the description.
+fn with_default(param: Param) -> Param {
+    let Some(equals_pos) = find_top_level(&param.param_type, '=') else {
+        return param;
+    };
+    let (param_type, default) = split_at_delimiter(&param.param_type, equals_pos, '=');
+    let mut default = default.to_string();
+    let mut description = param.description.as_str();
+    
+    if let Some(quote) = default.chars().next().filter(|c| *c == '"' || *c == '\'')
+        && (default.len() == 1 || !default.ends_with(quote))
+        && let Some(end) = description.find(quote)
+    {
+        default.push(' ');
+        default.push_str(&description[..=end]);
+        description = description[end + 1..].trim_start();
+    }
+    
+    Param {
+        param_type: param_type.trim().to_string(),
+        default: Some(default).filter(|default| !default.is_empty()),
+        description: description.to_string(),
+        ..param
+    }
+}
+
+fn split_param(content: &str) -> Option<Param> {
+    // `name:type description` and `name, type, description` only apply when the name is a single word
+    if let Some(colon_pos) = content.find(':')
+        && let (name, rest) = split_at_delimiter(content, colon_pos, ':')
+        && !name.trim().contains(' ')
+    {
+        let (param_type, description) = split_type(rest);
+        return Some(Param::new(name, param_type, description));
+    }
+    
+    if let Some(comma_pos) = find_top_level(content, ',')
+        && let (name, rest) = split_at_delimiter(content, comma_pos, ',')
+        && !name.trim().contains(' ')
+    {
+        let (param_type, description) = match find_top_level(rest, ',') {
+            Some(pos) => split_at_delimiter(rest, pos, ','),
+            None => (rest, ""),
+        };
+        return Some(Param::new(name, param_type, description));
+    }
+    
+    let (name, rest) = split_type(content);
+    let (param_type, description) = split_type(rest);
+    if !param_type.is_empty() {
+        return Some(Param::new(name, param_type, description));
     }
     
     None
 }
 
-fn extract_function_name(line: &str) -> Option<String> {
+/// Name of a table assigned on this line, as in `Config = {` or `local Config = {`.
+fn extract_table_name(line: &str) -> Option<String> {
+    let table_regex = Regex::new(r"^\s*(?:local\s+)?([a-zA-Z_][a-zA-Z0-9_]*(?:\.[a-zA-Z_][a-zA-Z0-9_]*)*)\s*=\s*\{").unwrap();
+    table_regex.captures(line).map(|captures| captures[1].to_string())
+}
+
+/// Name a value is assigned to on this line, `MAX_PLAYERS` for `local MAX_PLAYERS = 32`.
+fn extract_assignment_name(line: &str) -> Option<String> {
+    let assignment_regex = Regex::new(r"^\s*(?:local\s+)?([a-zA-Z_][a-zA-Z0-9_]*(?:\.[a-zA-Z_][a-zA-Z0-9_]*)*)\s*=(?:[^=]|$)").unwrap();
+    assignment_regex.captures(&code_only(line)).map(|captures| captures[1].to_string())
+}
+
+/// The code part of a line, with string contents blanked out and any trailing comment dropped,
+/// so `local s = "function foo()"` or `-- function bar()` is not taken for a declaration.
+fn code_only(line: &str) -> String {
+    let mut code = String::with_capacity(line.len());
+    let mut quote = None;
+    let mut chars = line.chars().peekable();
+    
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(_) if c == '\\' => {
+                chars.next();
+            }
+            Some(open) if c == open => {
+                quote = None;
+                code.push(c);
+            }
+            Some(_) => {}
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                code.push(c);
+            }
+            None if c == '-' && chars.peek() == Some(&'-') => break,
+            None => code.push(c),
+        }
+    }
+    
+    code
+}
+
+/// How many lines an argument list may span.
+const MAX_DECLARATION_LINES: usize = 5;
+
+/// The code of the declaration at `lines[index]`, joined with the lines after it until the
+/// argument list closes.
+fn declaration_text(lines: &[&str], index: usize) -> String {
+    let mut text = String::new();
+    for line in lines.iter().skip(index).take(MAX_DECLARATION_LINES) {
+        text.push_str(&code_only(line));
+        text.push(' ');
+        if text.find('(').is_some_and(|open| text[open..].contains(')')) {
+            break;
+        }
+    }
+    text
+}
+
+/// The line at `index`, joined with the next one when its code ends in `=`, so an assignment
+/// that puts `function` on the following line (`M.foo =` then `function(a)`) reads as one.
+fn continued_line(lines: &[&str], index: usize) -> String {
+    let line = lines[index];
+    let code = &line[..comment_start(line).unwrap_or(line.len())];
+    match lines.get(index + 1) {
+        Some(next) if code.trim_end().ends_with('=') => format!("{} {}", code.trim_end(), next.trim_start()),
+        _ => line.to_string(),
+    }
+}
+
+/// Name and argument names of the function declared in `text`, `None` when the argument
+/// list does not close. Default values such as `b = 1` are dropped from the names.
+fn extract_function_signature(text: &str) -> Option<(String, Vec<String>)> {
+    let (name, _) = extract_function_name(text)?;
+    
+    let code = code_only(text);
+    let start = code.find("function").map_or(0, |pos| pos + "function".len());
+    let open = start + code[start..].find('(')?;
+    let close = open + code[open..].find(')')?;
+    
+    let arguments = code[open + 1..close]
+        .split(',')
+        .map(|argument| argument.split('=').next().unwrap_or(argument).trim())
+        .filter(|argument| !argument.is_empty())
+        .map(String::from)
+        .collect();
+    Some((name, arguments))
+}
+
+/// Where a documented parameter sits in the declaration, `self` first and unknown ones last.
+fn declaration_position(param: &Param, arguments: &[String]) -> usize {
+    if param.name == "self" {
+        return 0;
+    }
+    arguments.iter()
+        .position(|argument| *argument == param.name || (param.variadic && argument == "..."))
+        .map_or(usize::MAX, |position| position + 1)
+}
+
+/// Name of the function declared on `line`, and whether it was declared with `:`.
+fn extract_function_name(line: &str) -> Option<(String, bool)> {
+    // string keys are read from the line as written, the code part has string contents blanked out
+    let raw = line;
+    let line = &code_only(line);
     let function_regex = Regex::new(r"function\s+([a-zA-Z_][a-zA-Z0-9_]*(?:\.[a-zA-Z_][a-zA-Z0-9_]*)*)\s*\(").unwrap();
     let local_function_regex = Regex::new(r"local\s+function\s+([a-zA-Z_][a-zA-Z0-9_]*)\s*\(").unwrap();
     let method_regex = Regex::new(r"([a-zA-Z_][a-zA-Z0-9_]*(?:\.[a-zA-Z_][a-zA-Z0-9_]*)*):([a-zA-Z_][a-zA-Z0-9_]*)\s*\(").unwrap();
     let assignment_regex = Regex::new(r"([a-zA-Z_][a-zA-Z0-9_]*(?:\.[a-zA-Z_][a-zA-Z0-9_]*)*)\s*=\s*function\s*\(").unwrap();
+    // a table field with a string key, `["on-join"] = function(`, named by the key
+    let key_regex = Regex::new(r#"^\s*\[\s*(?:"([^"]*)"|'([^']*)')\s*\]\s*=\s*function\s*\("#).unwrap();
+    
+    if let Some(captures) = key_regex.captures(raw) {
+        let key = captures.get(1).or_else(|| captures.get(2)).map_or("", |key| key.as_str());
+        return Some((key.to_string(), false));
+    }
     
     if let Some(captures) = function_regex.captures(line) {
-        return Some(captures[1].to_string());
+        return Some((captures[1].to_string(), false));
     }
     
     if let Some(captures) = local_function_regex.captures(line) {
-        return Some(captures[1].to_string());
+        return Some((captures[1].to_string(), false));
     }
     
     if let Some(captures) = method_regex.captures(line) {
-        return Some(format!("{}.{}", &captures[1], &captures[2]));
+        return Some((format!("{}.{}", &captures[1], &captures[2]), true));
     }
     
     if let Some(captures) = assignment_regex.captures(line) {
-        return Some(captures[1].to_string());
+        return Some((captures[1].to_string(), false));
     }
     
     None
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn parse_param_handles_multi_byte_text() {
+        let param = parse_param("größe number Die Größe in Metern 📏").unwrap();
+        assert_eq!(param.name, "größe");
+        assert_eq!(param.param_type, "number");
+        assert_eq!(param.description, "Die Größe in Metern 📏");
+        
+        let param = parse_param("naïve:string Déjà vu").unwrap();
+        assert_eq!(param.name, "naïve");
+        assert_eq!(param.param_type, "string");
+        assert_eq!(param.description, "Déjà vu");
+        
+        let param = parse_param("ñame, table<string, number>, Ñandú 🐦").unwrap();
+        assert_eq!(param.name, "ñame");
+        assert_eq!(param.param_type, "table<string, number>");
+        assert_eq!(param.description, "Ñandú 🐦");
+        
+        let param = parse_tparam("[opt] string ville Nom de la ville").unwrap();
+        assert!(param.optional);
+        assert_eq!(param.name, "ville");
+        
+        assert!(parse_param("🎉").is_none());
+    }
+    
+    #[test]
+    fn parse_returns_handles_multi_byte_text() {
+        let returns = parse_returns("string Texte accentué, number 🎉 compté", &BTreeSet::new());
+        assert_eq!(returns.len(), 2);
+        assert_eq!(returns[0].description, "Texte accentué");
+        assert_eq!(returns[1].return_type, "number");
+        assert_eq!(returns[1].description, "🎉 compté");
+        
+        let returns = parse_returns("Größe, die Größe", &BTreeSet::new());
+        assert_eq!(returns[0].return_type, "Größe");
+        assert_eq!(returns[0].description, "die Größe");
+    }
+    
+    #[test]
+    fn parse_returns_keeps_capitalized_words_in_the_description() {
+        let returns = parse_returns("boolean True if found, False otherwise", &BTreeSet::new());
+        assert_eq!(returns.len(), 1);
+        assert_eq!(returns[0].return_type, "boolean");
+        assert_eq!(returns[0].description, "True if found, False otherwise");
+        
+        let classes = BTreeSet::from([String::from("Player")]);
+        let returns = parse_returns("boolean Whether it was found, Player The player", &classes);
+        assert_eq!(returns.len(), 2);
+        assert_eq!(returns[1].return_type, "Player");
+        assert_eq!(returns[1].description, "The player");
+        
+        let returns = split_class_returns(parse_returns("boolean Found, Player The player", &BTreeSet::new()).remove(0), &classes);
+        assert_eq!(returns.len(), 2);
+    }
+    
+    #[test]
+    fn unknown_tags_suggest_the_closest_known_one() {
+        assert_eq!(closest_tag("retrun"), Some("return"));
+        assert_eq!(closest_tag("parm"), Some("param"));
+        assert_eq!(closest_tag("Deprecate"), Some("deprecated"));
+        assert_eq!(closest_tag("todo"), None);
+    }
+    
+    #[test]
+    fn splits_with_multi_byte_text_around_the_delimiter() {
+        let param = parse_param("é:ü Ω").unwrap();
+        assert_eq!(param.name, "é");
+        assert_eq!(param.param_type, "ü");
+        assert_eq!(param.description, "Ω");
+        
+        let param = parse_param("ñ,é,ü").unwrap();
+        assert_eq!(param.name, "ñ");
+        assert_eq!(param.param_type, "é");
+        assert_eq!(param.description, "ü");
+        
+        let returns = parse_returns("Ä,ö", &BTreeSet::new());
+        assert_eq!(returns[0].return_type, "Ä");
+        assert_eq!(returns[0].description, "ö");
+        
+        let returns = parse_returns("表 説明", &BTreeSet::new());
+        assert_eq!(returns[0].return_type, "表");
+        assert_eq!(returns[0].description, "説明");
+    }
+    
+    #[test]
+    fn dedent_keeps_multi_byte_indentation_intact() {
+        assert_eq!(dedent("  a\n    b"), "a\n  b");
+        // an ideographic space is content, not indentation to cut into
+        assert_eq!(dedent("\u{3000}\u{3000}x\n  y"), "\u{3000}\u{3000}x\n  y");
+    }
+    
+    #[test]
+    fn parses_accented_and_emoji_docs() {
+        let content = [
+            "--@class Joueur",
+            "--@desc Expulse un joueur 🚪 du serveur, même en équipe",
+            "--@param cible Joueur Le joueur à expulser",
+            "--@param raison? string La raison ✍️",
+            "--@return boolean Réussi",
+            "--@example",
+            "--  Joueur.Expulser(moi, \"Au revoir 👋\")",
+            "function Joueur.Expulser(cible, raison) end",
+            "",
+            "function Joueur.Saluer() -- @desc Dit « bonjour » 😀",
+            "end",
+        ].join("\n");
+        let parsed = parse_lua_content(&content, Path::new("joueur.lua"), "joueur.lua");
+        
+        assert_eq!(parsed.functions.len(), 2);
+        let (category, expulser) = &parsed.functions[0];
+        assert_eq!(category, "Joueur");
+        assert_eq!(expulser.name, "Expulser");
+        assert_eq!(expulser.description, "Expulse un joueur 🚪 du serveur, même en équipe");
+        assert_eq!(expulser.params[0].description, "Le joueur à expulser");
+        assert!(expulser.params[1].optional);
+        assert_eq!(expulser.params[1].description, "La raison ✍️");
+        assert_eq!(expulser.examples, ["Joueur.Expulser(moi, \"Au revoir 👋\")"]);
+        
+        let (_, saluer) = &parsed.functions[1];
+        assert_eq!(saluer.name, "Saluer");
+        assert_eq!(saluer.description, "Dit « bonjour » 😀");
+    }
+    
+    #[test]
+    fn crlf_and_bom_parse_like_lf() {
+        let lf = [
+            "--- Kicks a player",
+            "--@param target Player The player",
+            "--@return boolean Whether it worked",
+            "--@example",
+            "--  Player.Kick(ply)",
+            "function Player.Kick(target) end",
+            "",
+            "--[[",
+            "  @desc Bans a player",
+            "  @param target Player The player",
+            "]]",
+            "function Player.Ban(target) end",
+        ].join("\n");
+        let crlf = lf.replace('\n', "\r\n");
+        let bom = format!("\u{feff}{}", crlf);
+        
+        let expected = serde_json::to_string(&parse_lua_content(&lf, Path::new("player.lua"), "player.lua")).unwrap();
+        for content in [crlf, bom] {
+            let parsed = parse_lua_content(&content, Path::new("player.lua"), "player.lua");
+            assert_eq!(parsed.functions.len(), 2);
+            assert_eq!(serde_json::to_string(&parsed).unwrap(), expected);
+        }
+    }
+}
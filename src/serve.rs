@@ -0,0 +1,108 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::thread;
+
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::log;
+
+/// Serves `dir` on localhost:`port` from a background thread, so `--watch` can keep rebuilding
+/// into it. Binding happens before returning, a port that is already taken is reported right away.
+/// `base` is the path of `--base-url`, the site is served under it so links between pages work.
+pub fn spawn(dir: PathBuf, port: u16, base: String) -> Result<thread::JoinHandle<()>, Box<dyn std::error::Error>> {
+    let server = Server::http(("127.0.0.1", port))
+        .map_err(|error| format!("Failed to serve on port {}: {}", port, error))?;
+    let base = if base.is_empty() { String::from("/") } else { base };
+    log::info!("Serving {} at http://localhost:{}{}", dir.display(), port, base);
+    
+    Ok(thread::spawn(move || {
+        for request in server.incoming_requests() {
+            respond(&dir, &base, request);
+        }
+    }))
+}
+
+fn respond(dir: &Path, base: &str, request: Request) {
+    if !matches!(request.method(), Method::Get | Method::Head) {
+        let _ = request.respond(Response::from_string("Method not allowed").with_status_code(405));
+        return;
+    }
+    
+    let Some(mut path) = resolve(dir, base, request.url()) else {
+        let _ = request.respond(Response::from_string("Bad request").with_status_code(400));
+        return;
+    };
+    if path.is_dir() {
+        path.push("index.html");
+    }
+    
+    // the client hanging up early is not worth reporting
+    let _ = match fs::read(&path) {
+        Ok(content) => request.respond(
+            Response::from_data(content)
+                .with_header(header("Content-Type", mime_type(&path)))
+                // every rebuild replaces the files, the browser should not keep old ones
+                .with_header(header("Cache-Control", "no-cache"))
+        ),
+        Err(_) => request.respond(Response::from_string("Not found").with_status_code(404)),
+    };
+}
+
+/// Maps a request URL under `base` onto a file below `dir`, `None` when it tries to leave it.
+fn resolve(dir: &Path, base: &str, url: &str) -> Option<PathBuf> {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let path = percent_decode(path)?;
+    let path = match path.strip_prefix(base.trim_end_matches('/')) {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => rest,
+        _ => &path,
+    };
+    
+    let mut resolved = dir.to_path_buf();
+    for component in Path::new(path.trim_start_matches('/')).components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    Some(resolved)
+}
+
+fn percent_decode(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = text.get(i + 1..i + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+fn mime_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()).unwrap_or_default() {
+        "html" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" => "text/javascript; charset=utf-8",
+        "json" => "application/json",
+        "webmanifest" => "application/manifest+json",
+        "xml" => "application/xml",
+        "md" | "txt" => "text/plain; charset=utf-8",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "ico" => "image/x-icon",
+        _ => "application/octet-stream",
+    }
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name, value).unwrap()
+}
@@ -0,0 +1,103 @@
+use std::fs;
+use std::path::Path;
+
+use crate::scanner::{Categories, Documentation, Function};
+
+/// Writes the whole API as plain text to api.txt, compact enough to paste into a prompt.
+/// Categories and functions keep the order of the docs, so the file only changes with the API.
+pub fn generate_text(
+    docs: &Documentation,
+    categories: &Categories,
+    title: &str,
+    out_dir: &Path
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut text = format!("{} API\n", title);
+    
+    for (category, functions) in docs {
+        text.push_str(&format!("\n# {}\n", category));
+        if let Some(description) = categories.get(category) {
+            push_lines(&mut text, description, "");
+        }
+        
+        for func in functions {
+            text.push('\n');
+            text.push_str(&render_function(category, func));
+        }
+    }
+    
+    fs::write(out_dir.join("api.txt"), text)?;
+    Ok(())
+}
+
+fn render_function(category: &str, func: &Function) -> String {
+    let mut text = if func.table {
+        format!("{}\n", crate::qualified_name(category, func))
+    } else {
+        format!("{}\n", crate::render_signature(category, func))
+    };
+    
+    if let Some(message) = &func.deprecated {
+        if message.is_empty() {
+            text.push_str("  Deprecated\n");
+        } else {
+            text.push_str(&format!("  Deprecated: {}\n", plain(message)));
+        }
+    }
+    if func.readonly {
+        text.push_str("  Read-only\n");
+    }
+    push_lines(&mut text, &func.description, "  ");
+    for author in &func.authors {
+        text.push_str(&entry("author", author, "", ""));
+    }
+    
+    for param in &func.params {
+        let name = if param.optional { format!("{}?", param.display_name()) } else { param.display_name() };
+        let param_type = match &param.default {
+            Some(default) => format!("{}={}", param.param_type, default),
+            None => param.param_type.clone(),
+        };
+        text.push_str(&entry("param", &name, &param_type, &param.description));
+    }
+    for ret in &func.returns {
+        text.push_str(&entry("return", "", &ret.return_type, &ret.description));
+    }
+    for error in &func.errors {
+        text.push_str(&entry("throws", "", &error.return_type, &error.description));
+    }
+    for field in &func.fields {
+        let kind = if field.readonly { "readonly field" } else { "field" };
+        text.push_str(&entry(kind, &field.name, &field.field_type, &field.description));
+    }
+    
+    text
+}
+
+/// One indented `kind name type - description` line, leaving out the parts that are empty.
+fn entry(kind: &str, name: &str, entry_type: &str, description: &str) -> String {
+    let mut line = format!("  {}", kind);
+    for part in [name, entry_type] {
+        if !part.is_empty() {
+            line.push(' ');
+            line.push_str(part);
+        }
+    }
+    if !description.is_empty() {
+        line.push_str(" - ");
+        line.push_str(&plain(description));
+    }
+    line.push('\n');
+    line
+}
+
+/// Adds every non-empty line of `description` with the given indent.
+fn push_lines(text: &mut String, description: &str, indent: &str) {
+    for line in description.lines().filter(|line| !line.trim().is_empty()) {
+        text.push_str(&format!("{}{}\n", indent, plain(line)));
+    }
+}
+
+/// The text without the inline markup of descriptions.
+fn plain(text: &str) -> String {
+    text.replace(['`', '*'], "").split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,299 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::log;
+use crate::scanner::{self, Documentation, Function};
+
+/// Checks a hand-edited docs.json for `--validate`, printing every problem with the category
+/// and function it belongs to instead of stopping at the first one like deserializing does.
+pub fn validate_docs_json(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    log::info!("Validating {}", path.display());
+    let content = fs::read_to_string(path)
+        .map_err(|error| format!("Failed to read {}: {}", path.display(), error))?;
+    let value: Value = serde_json::from_str(&content)
+        .map_err(|error| format!("{} is not valid JSON: {}", path.display(), error))?;
+    
+    let mut problems = check_documentation(&value);
+    if problems.is_empty() {
+        // anything the checks above do not cover, such as a number where a string belongs
+        if let Err(error) = serde_json::from_value::<Documentation>(value.clone()) {
+            problems.push(error.to_string());
+        }
+    }
+    
+    for problem in &problems {
+        log::error!("{}", problem);
+    }
+    if !problems.is_empty() {
+        return Err(format!("{} problems found in {}", problems.len(), path.display()).into());
+    }
+    
+    let categories = value.as_object().map_or(0, Map::len);
+    let functions: usize = value.as_object()
+        .map_or(0, |docs| docs.values().filter_map(Value::as_array).map(Vec::len).sum());
+    log::ok!("{} is valid: {} functions in {} categories", path.display(), functions, categories);
+    Ok(())
+}
+
+fn check_documentation(docs: &Value) -> Vec<String> {
+    let mut problems = Vec::new();
+    let Some(docs) = docs.as_object() else {
+        problems.push("the top level must be an object of categories".to_string());
+        return problems;
+    };
+    
+    for (category, functions) in docs {
+        let Some(functions) = functions.as_array() else {
+            problems.push(format!("{}: must be a list of functions", category));
+            continue;
+        };
+        
+        for (index, func) in functions.iter().enumerate() {
+            let Some(func) = func.as_object() else {
+                problems.push(format!("{}[{}]: must be an object", category, index));
+                continue;
+            };
+            // name the function once it has one, its position otherwise
+            let path = match func.get("name").and_then(Value::as_str).filter(|name| !name.is_empty()) {
+                Some(name) => format!("{}.{}", category, name),
+                None => format!("{}[{}]", category, index),
+            };
+            
+            check_text(func, "name", &path, false, &mut problems);
+            check_text(func, "description", &path, true, &mut problems);
+            check_items(func, "params", &["name", "type"], &path, &mut problems);
+            check_items(func, "returns", &["type"], &path, &mut problems);
+            if func.contains_key("fields") {
+                check_items(func, "fields", &["name", "type"], &path, &mut problems);
+            }
+        }
+    }
+    
+    problems
+}
+
+/// Every entry of the `key` list must be an object with the `required` strings and a description.
+fn check_items(func: &Map<String, Value>, key: &str, required: &[&str], path: &str, problems: &mut Vec<String>) {
+    let Some(items) = func.get(key).and_then(Value::as_array) else {
+        problems.push(format!("{}: {} must be a list", path, key));
+        return;
+    };
+    
+    for (index, item) in items.iter().enumerate() {
+        let item_path = format!("{} {}[{}]", path, key, index);
+        let Some(item) = item.as_object() else {
+            problems.push(format!("{}: must be an object", item_path));
+            continue;
+        };
+        for field in required {
+            check_text(item, field, &item_path, false, problems);
+        }
+        check_text(item, "description", &item_path, true, problems);
+    }
+}
+
+fn check_text(object: &Map<String, Value>, key: &str, path: &str, allow_empty: bool, problems: &mut Vec<String>) {
+    match object.get(key) {
+        None => problems.push(format!("{}: missing \"{}\"", path, key)),
+        Some(Value::String(text)) if text.trim().is_empty() && !allow_empty => {
+            problems.push(format!("{}: \"{}\" is empty", path, key));
+        }
+        Some(Value::String(_)) => {}
+        Some(_) => problems.push(format!("{}: \"{}\" must be a string", path, key)),
+    }
+}
+
+/// A problem found in the documentation, written to `diagnostics.json` with `--diagnostics`
+/// so editors can show it at the line of the function.
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    pub file: String,
+    pub line: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Logged while building and counted in the summary.
+    Warning,
+    /// Only counted in the summary, such as a function without a description.
+    Info,
+}
+
+impl Diagnostic {
+    fn new(func: &Function, severity: Severity, message: String) -> Self {
+        Self::at(&func.source_file, func.source_line, severity, message)
+    }
+    
+    /// A problem at `file:line` that is not about a documented function, such as an unknown tag.
+    pub fn at(file: &str, line: usize, severity: Severity, message: String) -> Self {
+        Self {
+            file: file.to_string(),
+            line,
+            severity,
+            message,
+        }
+    }
+}
+
+/// Logs a warning about `func` and keeps it for `diagnostics.json`.
+fn warn(diagnostics: &mut Vec<Diagnostic>, func: &Function, message: String) {
+    log::warning!("{}", message);
+    diagnostics.push(Diagnostic::new(func, Severity::Warning, message));
+}
+
+/// Warns about every function documented more than once in the same category,
+/// returning how many duplicates were found.
+pub fn report_duplicates(docs: &Documentation, diagnostics: &mut Vec<Diagnostic>) -> usize {
+    let mut duplicates = 0;
+    
+    for (category, functions) in docs {
+        let mut seen = HashMap::new();
+        for func in functions {
+            if let Some(first) = seen.insert(func.name.as_str(), func) {
+                warn(diagnostics, func, format!(
+                    "Duplicate function {}.{} documented at {} and {}",
+                    category, func.name, first.location(), func.location()
+                ));
+                duplicates += 1;
+            }
+        }
+    }
+    
+    duplicates
+}
+
+/// Warns about every argument of a declaration without a matching `--@param`, and every
+/// `--@param` the declaration does not have, returning how many mismatches were found.
+/// `self` and variadic arguments are left out of the comparison.
+pub fn report_param_mismatches(docs: &Documentation, diagnostics: &mut Vec<Diagnostic>) -> usize {
+    let mut mismatches = 0;
+    
+    for (category, functions) in docs {
+        for func in functions {
+            let Some((declared, documented)) = compared_params(func) else {
+                continue;
+            };
+            
+            for argument in declared.iter().filter(|argument| !documented.contains(argument)) {
+                warn(diagnostics, func, format!(
+                    "Parameter {} of {}.{} is not documented at {}",
+                    argument, category, func.name, func.location()
+                ));
+                mismatches += 1;
+            }
+            for param in documented.iter().filter(|param| !declared.contains(param)) {
+                warn(diagnostics, func, format!(
+                    "Documented parameter {} of {}.{} is not in its declaration at {}",
+                    param, category, func.name, func.location()
+                ));
+                mismatches += 1;
+            }
+        }
+    }
+    
+    mismatches
+}
+
+/// Counts for the build summary: functions without a description, and functions with a declared
+/// argument that has no `--@param`.
+pub fn count_incomplete(docs: &Documentation) -> (usize, usize) {
+    let functions = docs.values().flatten();
+    let undescribed = functions.clone()
+        .filter(|func| func.description.trim().is_empty())
+        .count();
+    let undocumented = functions
+        .filter(|func| compared_params(func).is_some_and(|(declared, documented)| {
+            declared.iter().any(|argument| !documented.contains(argument))
+        }))
+        .count();
+    (undescribed, undocumented)
+}
+
+/// The declared arguments and the documented parameters of a function, without `self` and
+/// variadic ones. `None` when its declaration was not found.
+fn compared_params(func: &Function) -> Option<(Vec<&str>, Vec<&str>)> {
+    let arguments = func.arguments.as_ref()?;
+    let declared = arguments.iter()
+        .map(String::as_str)
+        .filter(|argument| *argument != "self" && *argument != "...")
+        .collect();
+    let documented = func.params.iter()
+        .filter(|param| param.name != "self" && !param.variadic)
+        .map(|param| param.name.as_str())
+        .collect();
+    Some((declared, documented))
+}
+
+/// Warns about every alias that is also the name of a documented function, which it would
+/// shadow in navigation and search, returning how many were found.
+pub fn report_alias_collisions(docs: &Documentation, diagnostics: &mut Vec<Diagnostic>) -> usize {
+    let mut collisions = 0;
+    
+    for (category, functions) in docs {
+        for func in functions {
+            for alias in &func.aliases {
+                // a bare alias lives in the category of its function
+                let reference = if alias.contains(['.', ':']) {
+                    alias.clone()
+                } else {
+                    format!("{}.{}", category, alias)
+                };
+                
+                if let Some((target_category, target)) = scanner::resolve_qualified(docs, &reference) {
+                    warn(diagnostics, func, format!(
+                        "Alias {} of {}.{} collides with {}.{} documented at {}",
+                        alias, category, func.name, target_category, target.name, target.location()
+                    ));
+                    collisions += 1;
+                }
+            }
+        }
+    }
+    
+    collisions
+}
+
+/// Warns about every `--@see` that names no documented function, returning how many were found.
+pub fn report_unresolved_references(docs: &Documentation, diagnostics: &mut Vec<Diagnostic>) -> usize {
+    let mut unresolved = 0;
+    
+    for (category, functions) in docs {
+        for func in functions {
+            for reference in func.see.iter().filter(|reference| scanner::resolve_reference(docs, category, reference).is_none()) {
+                warn(diagnostics, func, format!(
+                    "Unresolved reference {} in {}.{} at {}",
+                    reference, category, func.name, func.location()
+                ));
+                unresolved += 1;
+            }
+        }
+    }
+    
+    unresolved
+}
+
+/// Notes every function without a description, returning how many were found. These are only
+/// counted in the build summary unless `required`, which makes each one a warning.
+pub fn report_undescribed(docs: &Documentation, diagnostics: &mut Vec<Diagnostic>, required: bool) -> usize {
+    let mut undescribed = 0;
+    
+    for (category, functions) in docs {
+        for func in functions.iter().filter(|func| func.description.trim().is_empty()) {
+            let message = format!("Function {}.{} has no description at {}", category, func.name, func.location());
+            if required {
+                warn(diagnostics, func, message);
+            } else {
+                diagnostics.push(Diagnostic::new(func, Severity::Info, message));
+            }
+            undescribed += 1;
+        }
+    }
+    
+    undescribed
+}
@@ -0,0 +1,99 @@
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::cli::Args;
+use crate::log;
+
+/// How long to wait after the last change before rebuilding, so a burst of saves rebuilds once.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+enum WatchEvent {
+    Changed,
+    Stop,
+}
+
+/// Watches the sources and templates and calls `rebuild` after every change until Ctrl-C.
+pub fn watch<F>(args: &Args, rebuild: F) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: Fn() -> Result<(), Box<dyn std::error::Error>>,
+{
+    let (sender, receiver) = mpsc::channel();
+    
+    let stop_sender = sender.clone();
+    ctrlc::set_handler(move || {
+        let _ = stop_sender.send(WatchEvent::Stop);
+    })?;
+    
+    let sources = if !args.src.is_empty() {
+        args.src.clone()
+    } else {
+        vec![args.path.clone().unwrap_or_else(|| args.input.clone())]
+    };
+    let sources = sources.iter()
+        .map(|source| source.canonicalize())
+        .collect::<Result<Vec<_>, _>>()?;
+    let template_dir = args.template_dir.canonicalize()?;
+    
+    let output_dir = canonical(&args.output);
+    let input = canonical(&args.input);
+    let watch_template = template_dir.clone();
+    let reads_input = sources.contains(&input);
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<Event>| {
+        let Ok(event) = result else { return };
+        // reading the sources during a rebuild must not count as a change
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        
+        // docs.json only counts when it is what we read, otherwise writing it would rebuild forever
+        let relevant = event.paths.iter().any(|path| {
+            !path.starts_with(&output_dir)
+                && (path.starts_with(&watch_template)
+                    || path.extension().is_some_and(|ext| ext == "lua")
+                    || (*path == input && reads_input))
+        });
+        if relevant {
+            let _ = sender.send(WatchEvent::Changed);
+        }
+    })?;
+    
+    for source in &sources {
+        watcher.watch(source, RecursiveMode::Recursive)?;
+        log::info!("Watching {} for changes", source.display());
+    }
+    watcher.watch(&template_dir, RecursiveMode::Recursive)?;
+    log::info!("Watching {} for changes, press Ctrl-C to stop", template_dir.display());
+    
+    while let Ok(WatchEvent::Changed) = receiver.recv() {
+        // swallow the rest of the burst before rebuilding
+        loop {
+            match receiver.recv_timeout(DEBOUNCE) {
+                Ok(WatchEvent::Changed) => continue,
+                Ok(WatchEvent::Stop) => return stopped(),
+                Err(mpsc::RecvTimeoutError::Timeout) => break,
+                Err(mpsc::RecvTimeoutError::Disconnected) => return stopped(),
+            }
+        }
+        
+        let time = chrono::Local::now().format("%H:%M:%S");
+        match rebuild() {
+            Ok(()) => log::ok!("{} rebuilt", time),
+            Err(error) => log::error!("{} rebuild failed: {}", time, error),
+        }
+    }
+    
+    stopped()
+}
+
+fn stopped() -> Result<(), Box<dyn std::error::Error>> {
+    log::info!("Stopped watching");
+    Ok(())
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize()
+        .or_else(|_| std::path::absolute(path))
+        .unwrap_or_else(|_| path.to_path_buf())
+}
@@ -1,9 +1,152 @@
 function initSearch() {
     const searchBox = document.getElementById('search');
+    const searchResults = document.getElementById('search-results');
     const functions = document.querySelectorAll('.function');
     const navItems = document.querySelectorAll('.nav-item');
+    let searchIndex = window.searchIndex || [];
+
+    // the single page inlines its index, every other page loads it
+    if (!window.searchIndex) {
+        fetch('search-index.json')
+            .then(response => response.json())
+            .then(entries => { searchIndex = entries; })
+            .catch(() => { searchIndex = []; });
+    }
+
+    // higher is better: exact, prefix and substring hits first, then typos such as "plyr" for "player"
+    function matchScore(text, query) {
+        if (text === query) {
+            return 100;
+        }
+        if (text.startsWith(query)) {
+            return 80;
+        }
+        if (text.includes(query)) {
+            return 60;
+        }
+        if (query.length < 3 || text[0] !== query[0]) {
+            return 0;
+        }
+
+        // every letter in order, scored by how close together they sit
+        let position = 0;
+        let gaps = 0;
+        for (const char of query) {
+            const found = text.indexOf(char, position);
+            if (found === -1) {
+                return 0;
+            }
+            gaps += found - position;
+            position = found + 1;
+        }
+        return Math.max(1, 40 - gaps * 5);
+    }
+
+    function entryScore(entry, query) {
+        const tokens = entry.tokens || [entry.name, entry.category, entry.description].map(text => text.toLowerCase());
+        let total = 0;
+
+        for (const word of query.split(/\s+/).filter(Boolean)) {
+            let best = 0;
+            tokens.forEach(token => { best = Math.max(best, matchScore(token, word)); });
+            if (best === 0) {
+                return 0;
+            }
+            // hits on the name count the most
+            total += best + matchScore(entry.name.toLowerCase(), word) / 2;
+        }
+        return total;
+    }
+
+    // result highlighted with the arrow keys, -1 while none is
+    let activeResult = -1;
+
+    function renderResults(query) {
+        searchResults.innerHTML = '';
+        setActiveResult(-1);
+        if (!query) {
+            return;
+        }
+
+        searchIndex
+            .map(entry => ({ entry, score: entryScore(entry, query) }))
+            .filter(result => result.score > 0)
+            .sort((a, b) => b.score - a.score || a.entry.name.localeCompare(b.entry.name))
+            .slice(0, 20)
+            .map(result => result.entry)
+            .forEach((entry, index) => {
+                const item = document.createElement('li');
+                item.className = 'search-result';
+                item.id = 'search-result-' + index;
+                item.setAttribute('role', 'option');
+
+                const link = document.createElement('a');
+                link.href = entry.href;
+                link.className = 'search-result-link';
+                link.textContent = entry.category + '.' + entry.name;
+
+                const desc = document.createElement('div');
+                desc.className = 'search-result-desc';
+                desc.textContent = entry.description;
+
+                item.appendChild(link);
+                item.appendChild(desc);
+                searchResults.appendChild(item);
+            });
+    }
+
+    // the search box keeps focus, the highlighted result is announced through aria-activedescendant
+    function setActiveResult(index) {
+        const items = searchResults.querySelectorAll('.search-result');
+        activeResult = index;
+        items.forEach((item, i) => {
+            item.classList.toggle('active', i === index);
+            item.setAttribute('aria-selected', i === index);
+        });
+
+        if (index >= 0) {
+            searchBox.setAttribute('aria-activedescendant', items[index].id);
+            items[index].scrollIntoView({ block: 'nearest' });
+        } else {
+            searchBox.removeAttribute('aria-activedescendant');
+        }
+    }
+
+    searchBox.addEventListener('keydown', function(e) {
+        const count = searchResults.querySelectorAll('.search-result').length;
+
+        if (e.key === 'ArrowDown' && count > 0) {
+            e.preventDefault();
+            setActiveResult((activeResult + 1) % count);
+        } else if (e.key === 'ArrowUp' && count > 0) {
+            e.preventDefault();
+            setActiveResult(activeResult <= 0 ? count - 1 : activeResult - 1);
+        } else if (e.key === 'Enter' && count > 0) {
+            // without a highlighted result Enter opens the best match
+            const link = searchResults.querySelectorAll('.search-result-link')[Math.max(activeResult, 0)];
+            e.preventDefault();
+            window.location.href = link.href;
+        } else if (e.key === 'Escape') {
+            this.value = '';
+            this.dispatchEvent(new Event('input'));
+            this.blur();
+        }
+    });
+
+    // `/` jumps to the search box from anywhere except another text field
+    document.addEventListener('keydown', function(e) {
+        const target = e.target;
+        const typing = target.isContentEditable || ['INPUT', 'TEXTAREA', 'SELECT'].includes(target.tagName);
+        if (e.key === '/' && !typing && !e.ctrlKey && !e.metaKey && !e.altKey) {
+            e.preventDefault();
+            searchBox.focus();
+            searchBox.select();
+        }
+    });
     
-    document.querySelectorAll('.nav-link').forEach(link => {
+    const sectionLinks = document.querySelectorAll('.nav-link, .toc-link');
+
+    sectionLinks.forEach(link => {
         link.addEventListener('click', function(e) {
             e.preventDefault();
             const href = this.getAttribute('href');
@@ -21,8 +164,7 @@ function initSearch() {
                     
                     history.pushState(null, null, href);
 
-                    document.querySelectorAll('.nav-link').forEach(l => l.classList.remove('active'));
-                    this.classList.add('active');
+                    setActive(targetId);
                 }
             } else {
                 window.location.href = href;
@@ -30,15 +172,18 @@ function initSearch() {
         });
     });
 
-    document.querySelectorAll('.nav-title').forEach(title => {
-        title.addEventListener('click', function() {
-            const section = this.parentElement;
-            section.classList.toggle('collapsed');
+    document.querySelectorAll('.permalink').forEach(link => {
+        link.addEventListener('click', function() {
+            const url = location.href.split('#')[0] + this.getAttribute('href');
+            if (navigator.clipboard) {
+                navigator.clipboard.writeText(url).catch(() => {});
+            }
         });
     });
-    
+
     searchBox.addEventListener('input', function() {
         const query = this.value.toLowerCase();
+        renderResults(query);
         
         functions.forEach(func => {
             const name = func.dataset.name.toLowerCase();
@@ -57,32 +202,97 @@ function initSearch() {
             
             if (name.includes(query)) {
                 item.style.display = 'block';
-                const section = item.closest('.nav-section');
-                section.classList.remove('collapsed');
+                // show matches in categories that are folded away
+                if (query) {
+                    item.closest('.nav-section').open = true;
+                }
             } else {
                 item.style.display = 'none';
             }
         });
     });
         
+    // highlights the sidebar and table of contents entries of the section being read,
+    // links into the current page name the id of their section in data-target
+    function setActive(id) {
+        sectionLinks.forEach(link => {
+            link.classList.toggle('active', link.dataset.target === id);
+        });
+
+        // keep the active entry in view when the table of contents is longer than the screen
+        const toc = document.querySelector('.toc-sidebar');
+        const current = document.querySelector('.toc-link.active');
+        if (toc && current) {
+            toc.scrollTop = current.offsetTop - toc.clientHeight / 2;
+        }
+    }
+
+    // a section is active while it crosses a thin band near the top of the screen,
+    // which also works for sections taller than the screen
     const observer = new IntersectionObserver(entries => {
         entries.forEach(entry => {
             if (entry.isIntersecting) {
-                const id = entry.target.id;
-                document.querySelectorAll('.nav-link').forEach(link => {
-                    link.classList.remove('active');
-                    if (link.getAttribute('href') === '#' + id) {
-                        link.classList.add('active');
-                    }
-                });
+                setActive(entry.target.id);
             }
         });
     }, { 
-        threshold: 0.5,
+        threshold: 0,
         rootMargin: '-10% 0px -85% 0px'
     });
     
-    functions.forEach(func => observer.observe(func));
+    // every function or enum an entry leads to, each observed once even when aliases share it
+    const sections = new Set();
+    sectionLinks.forEach(link => {
+        const section = link.dataset.target && document.getElementById(link.dataset.target);
+        if (section) {
+            sections.add(section);
+        }
+    });
+    sections.forEach(section => observer.observe(section));
 }
 
-document.addEventListener('DOMContentLoaded', initSearch);
\ No newline at end of file
+document.addEventListener('DOMContentLoaded', initSearch);
+
+// collapsed sections would print as just their title, open them for the print and close them again after
+let closedForPrint = [];
+window.addEventListener('beforeprint', () => {
+    closedForPrint = [...document.querySelectorAll('details:not([open])')];
+    closedForPrint.forEach(details => details.open = true);
+});
+window.addEventListener('afterprint', () => {
+    closedForPrint.forEach(details => details.open = false);
+    closedForPrint = [];
+});
+
+// example blocks keep their source without the highlighting markup in data-code, that is what gets copied
+document.addEventListener('click', event => {
+    const button = event.target.closest('.copy-button');
+    if (!button) {
+        return;
+    }
+    
+    copyText(button.dataset.code).then(() => {
+        button.textContent = 'Copied!';
+        clearTimeout(button.resetTimer);
+        button.resetTimer = setTimeout(() => { button.textContent = 'Copy'; }, 1500);
+    }).catch(() => {
+        button.textContent = 'Copy failed';
+    });
+});
+
+// the clipboard API only exists on https and localhost, pages opened from disk fall back to a selection
+function copyText(text) {
+    if (navigator.clipboard && window.isSecureContext) {
+        return navigator.clipboard.writeText(text);
+    }
+    
+    const area = document.createElement('textarea');
+    area.value = text;
+    area.style.position = 'fixed';
+    area.style.opacity = '0';
+    document.body.appendChild(area);
+    area.select();
+    const copied = document.execCommand('copy');
+    area.remove();
+    return copied ? Promise.resolve() : Promise.reject();
+}
@@ -0,0 +1,142 @@
+//! Runs the generator over `fixtures/tricky.json` and checks that every page it writes is
+//! well-formed HTML with the functions and escaping the fixture asks for.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use scraper::{Html, Selector};
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/tricky.json");
+const TEMPLATE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/template");
+
+/// Generates the fixture into a fresh folder named after `name`, with `extra` arguments.
+fn generate(name: &str, extra: &[&str]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("xenorlua-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let output = dir.join("dist");
+
+    // run inside the folder so no xenorlua.toml of the checkout is picked up
+    let status = Command::new(env!("CARGO_BIN_EXE_XenorLuaGenerator"))
+        .current_dir(&dir)
+        .args(["--quiet", "--input", FIXTURE, "--template-dir", TEMPLATE_DIR])
+        .arg("--output")
+        .arg(&output)
+        .args(extra)
+        .status()
+        .unwrap();
+    assert!(status.success(), "the generator failed on the fixture");
+    output
+}
+
+/// Parses a page, failing on anything the HTML parser had to repair.
+fn parse_page(path: &Path) -> Html {
+    let html = Html::parse_document(&fs::read_to_string(path).unwrap());
+    assert!(html.errors.is_empty(), "{} is malformed: {:?}", path.display(), html.errors);
+    html
+}
+
+fn select<'a>(html: &'a Html, selector: &str) -> Vec<scraper::ElementRef<'a>> {
+    html.select(&Selector::parse(selector).unwrap()).collect()
+}
+
+fn text(element: &scraper::ElementRef) -> String {
+    element.text().collect()
+}
+
+#[test]
+fn every_page_is_well_formed() {
+    let output = generate("pages", &[]);
+    let pages: Vec<PathBuf> = fs::read_dir(&output).unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "html"))
+        .collect();
+
+    assert!(pages.len() >= 3, "expected the overview and a page per category, got {:?}", pages);
+    for page in pages {
+        parse_page(&page);
+    }
+}
+
+#[test]
+fn category_page_has_one_function_per_entry_with_unique_ids() {
+    let output = generate("functions", &[]);
+    let page = parse_page(&output.join("player.html"));
+
+    let ids: Vec<&str> = select(&page, ".function").iter()
+        .map(|function| function.value().attr("id").unwrap())
+        .collect();
+    assert_eq!(ids, ["kick", "say", "kick-1"]);
+
+    let net = parse_page(&output.join("net.html"));
+    let ids: Vec<&str> = select(&net, ".function").iter()
+        .map(|function| function.value().attr("id").unwrap())
+        .collect();
+    assert_eq!(ids, ["send", "grosse"]);
+}
+
+#[test]
+fn descriptions_and_examples_are_escaped() {
+    let output = generate("escaping", &[]);
+    let page = parse_page(&output.join("player.html"));
+
+    // nothing from the descriptions turns into markup of its own
+    assert!(select(&page, ".function script").is_empty());
+    assert!(select(&page, ".function-description b").is_empty());
+
+    let description = text(&select(&page, "#kick .function-description")[0]);
+    assert_eq!(description, "Kicks <b>a player</b> & says \"bye\" to 'them' 👋");
+
+    let param = text(&select(&page, "#kick .param-desc")[0]);
+    assert_eq!(param, "Who gets <script>alert(1)</script> kicked");
+
+    let example = select(&page, "#kick .example-block")[0];
+    let code = "Player.Kick(ply, { [\"a&b\"] = 1 }) -- </code></pre> \"quoted\"";
+    assert_eq!(text(&select(&page, "#kick .example code")[0]), code);
+    let button = example.select(&Selector::parse(".copy-button").unwrap()).next().unwrap();
+    assert_eq!(button.value().attr("data-code"), Some(code));
+
+    // inline code keeps its content as text
+    let say = text(&select(&page, "#say .function-description code")[0]);
+    assert_eq!(say, "<text>");
+}
+
+#[test]
+fn see_also_links_point_at_existing_anchors() {
+    let output = generate("links", &[]);
+    let page = parse_page(&output.join("player.html"));
+
+    let links: Vec<&str> = select(&page, "#kick .see-item a").iter()
+        .map(|link| link.value().attr("href").unwrap())
+        .collect();
+    assert_eq!(links, ["#say", "net.html#send"]);
+
+    let net = parse_page(&output.join("net.html"));
+    assert_eq!(select(&net, "#send").len(), 1);
+}
+
+#[test]
+fn single_page_is_well_formed_with_unique_ids() {
+    let output = generate("single", &["--single-page"]);
+    let page = parse_page(&output.join("index.html"));
+
+    let ids: Vec<&str> = select(&page, ".function").iter()
+        .map(|function| function.value().attr("id").unwrap())
+        .collect();
+    assert_eq!(ids.len(), 5);
+    let unique: std::collections::BTreeSet<&&str> = ids.iter().collect();
+    assert_eq!(unique.len(), ids.len(), "ids repeat on the single page: {:?}", ids);
+}
+
+#[test]
+fn minified_pages_parse_and_keep_code_blocks_as_written() {
+    let plain = parse_page(&generate("plain", &[]).join("player.html"));
+    let minified = parse_page(&generate("minified", &["--minify"]).join("player.html"));
+
+    let blocks = |page: &Html| select(page, "pre").iter().map(text).collect::<Vec<_>>();
+    assert_eq!(blocks(&minified), blocks(&plain));
+    assert!(blocks(&minified).contains(&String::from(
+        "if not Player.Kick(ply) then\n    print(\"kept   as  is\")\n\n    return\nend"
+    )));
+}
@@ -0,0 +1,77 @@
+//! Uses the generator as a library, the way a downstream tool embeds it.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use xenor_lua_generator::{Options, generate, parse_lua_content, scan_directory};
+
+const PLAYER: &str = "\
+--@class Player
+--@desc Everything about the people on the server
+
+--@enum Player.Team
+--@field Red 1 The red team
+--@field Blue 2 The blue team
+Player.Team = { Red = 1, Blue = 2 }
+
+--@desc Kicks a player
+--@param target Player Who
+function Player.Kick(target) end
+
+--@desc Only used by the admin menu
+--@private
+function Player.Reset() end
+";
+
+/// A fresh folder below the temp folder, named after `name`.
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("xenorlua-library-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn options(output: PathBuf) -> Options {
+    Options {
+        template_dir: concat!(env!("CARGO_MANIFEST_DIR"), "/template").into(),
+        output,
+        title: String::from("Example"),
+        ..Options::default()
+    }
+}
+
+#[test]
+fn generates_the_site_a_build_would() {
+    let dir = temp_dir("site");
+    fs::create_dir_all(dir.join("lua")).unwrap();
+    fs::write(dir.join("lua/player.lua"), PLAYER).unwrap();
+
+    let scanned = scan_directory(&dir.join("lua")).unwrap();
+    assert_eq!(scanned.enums["Player"][0].name, "Team");
+    assert_eq!(scanned.categories["Player"], "Everything about the people on the server");
+
+    generate(&scanned, &options(dir.join("dist"))).unwrap();
+    let page = fs::read_to_string(dir.join("dist/player.html")).unwrap();
+    assert!(page.contains("Everything about the people on the server"));
+    assert!(page.contains(r#"<td class="enum-name">Red</td>"#));
+    assert!(page.contains("Kicks a player"));
+    assert!(!page.contains("Only used by the admin menu"), "private functions are left out by default");
+    assert!(fs::read_to_string(dir.join("dist/index.html")).unwrap().contains("player.html"));
+
+    let with_private = Options { include_private: true, ..options(dir.join("private")) };
+    generate(&scanned, &with_private).unwrap();
+    let page = fs::read_to_string(dir.join("private/player.html")).unwrap();
+    assert!(page.contains("Only used by the admin menu"));
+}
+
+#[test]
+fn parses_content_without_a_file() {
+    let scanned = parse_lua_content(PLAYER, Path::new("lua/player.lua"));
+
+    let kick = &scanned.docs["Player"][0];
+    assert_eq!(kick.name, "Kick");
+    assert_eq!(kick.description, "Kicks a player");
+    assert_eq!(kick.params[0].param_type, "Player");
+    assert_eq!(kick.source_file, "lua/player.lua");
+    assert_eq!(scanned.enums["Player"][0].variants.len(), 2);
+}